@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+use clap::{ArgAction, Args, Parser, Subcommand};
+use globset::Glob;
+use crate::output_format::OutputFormat;
+
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about = "Reduces a repository to its declarations and summarises it",
+    after_help = "Exit codes: 0 on success, 1 if the run failed, 2 for invalid arguments."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+
+    /// Only process files of this language, e.g. `rust` or `python` (repeatable)
+    #[arg(short, long = "language", value_name = "LANGUAGE", global = true)]
+    pub languages: Vec<String>,
+
+    /// Only process files whose path relative to the input matches (repeatable)
+    #[arg(long, value_name = "GLOB", value_parser = Glob::new, global = true)]
+    pub include: Vec<Glob>,
+
+    /// Skip files and directories whose path relative to the input matches (repeatable)
+    #[arg(long, value_name = "GLOB", value_parser = Glob::new, global = true)]
+    pub exclude: Vec<Glob>,
+
+    /// Number of files processed at once, defaults to the number of CPUs
+    #[arg(short = 'j', long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), global = true)]
+    pub concurrency: Option<u32>,
+
+    /// Report every processed file on stderr
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Only report errors that stop the run
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Write the simplified files and a summary to an output directory
+    Summarize(SummarizeArgs),
+    /// Print the directory tree
+    Tree(PrintArgs),
+    /// Print every function, class and type with its line range
+    Symbols(PrintArgs),
+    /// Print file and line counts per language
+    Stats(PrintArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SummarizeArgs {
+    /// Git repository URL or directory path
+    pub input: String,
+
+    /// Output directory, defaults to `_arch_<repo name>-<short commit>` in the working directory
+    #[arg(short, long, value_name = "DIR")]
+    pub output: Option<PathBuf>,
+
+    /// Replace the output directory if it already exists
+    #[arg(long)]
+    pub overwrite: bool,
+
+    /// Name the default output directory `_arch_<6 random chars>`
+    #[arg(long, conflicts_with = "output")]
+    pub random_suffix: bool,
+
+    /// Format of the summary file
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Also write a symbol listing next to the summary
+    #[arg(long)]
+    pub index: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PrintArgs {
+    /// Git repository URL or directory path
+    pub input: String,
+
+    /// Format of what is printed
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
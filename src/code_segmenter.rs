@@ -1,6 +1,187 @@
+use std::ops::Range;
+use serde::Serialize;
+use tree_sitter::Node;
+
 pub(crate) trait CodeSegmenter: Send {
     fn simplify_code(&self) -> String;
-    #[allow(dead_code)]
-    fn extract_functions_classes(&self) -> String;
+    /// Every function, class and type, nested under the symbol that declares it.
+    fn extract_symbols(&self) -> Vec<Symbol>;
+    /// Name of the implementing type, e.g. `PythonSegmenter`.
+    fn name(&self) -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+        type_name.rsplit("::").next().unwrap_or(type_name)
+    }
+}
+
+/// Constructor registered per language in `main.rs`.
+pub(crate) type SegmenterFn = fn(String) -> Box<dyn CodeSegmenter>;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Symbol {
+    pub kind: &'static str,
+    pub name: String,
+    pub qualified_name: String,
+    /// The declaration up to its body, on a single line.
+    pub signature: String,
+    pub visibility: Option<String>,
+    /// First sentence of the doc comment or docstring.
+    pub doc: Option<String>,
+    pub byte_range: Range<usize>,
+    /// 1-based and inclusive.
+    pub start_line: usize,
+    pub end_line: usize,
+    pub children: Vec<Symbol>,
+}
+
+impl Symbol {
+    pub fn from_node(kind: &'static str, name: &str, qualified_name: &str, node: Node, source_code: &str) -> Symbol {
+        let signature = get_signature(node, source_code);
+        Symbol {
+            kind,
+            name: name.to_string(),
+            qualified_name: qualified_name.to_string(),
+            visibility: get_visibility(&signature),
+            signature,
+            doc: get_doc_summary(node, source_code),
+            byte_range: node.start_byte()..node.end_byte(),
+            start_line: node.start_position().row + 1,
+            end_line: node.end_position().row + 1,
+            children: Vec::new(),
+        }
+    }
+
+    /// For segmenters that read the source line by line. Lines are 1-based.
+    pub fn from_lines(kind: &'static str, name: &str, qualified_name: &str, start_line: usize, end_line: usize, source_code: &str) -> Symbol {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(source_code.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        let start_byte = line_starts.get(start_line - 1).copied().unwrap_or(source_code.len());
+        let end_byte = line_starts.get(end_line).map(|start| start - 1).unwrap_or(source_code.len());
+        let signature = clean_signature(source_code[start_byte..end_byte].lines().next().unwrap_or(""));
+        Symbol {
+            kind,
+            name: name.to_string(),
+            qualified_name: qualified_name.to_string(),
+            visibility: get_visibility(&signature),
+            signature,
+            doc: None,
+            byte_range: start_byte..end_byte,
+            start_line,
+            end_line,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// One line per symbol, depth first, e.g. `method User.save L10-42`.
+pub(crate) fn render_index(symbols: &[Symbol]) -> String {
+    let mut lines = Vec::new();
+    let mut stack: Vec<&Symbol> = symbols.iter().rev().collect();
+    while let Some(symbol) = stack.pop() {
+        lines.push(format_index_entry(symbol.kind, &symbol.qualified_name, symbol.start_line, symbol.end_line));
+        stack.extend(symbol.children.iter().rev());
+    }
+    lines.join("\n")
+}
+
+/// Formats one index line, e.g. `method User.save L10-42`. Lines are 1-based.
+fn format_index_entry(kind: &str, qualified_name: &str, start_line: usize, end_line: usize) -> String {
+    format!("{} {} L{}-{}", kind, qualified_name, start_line, end_line)
+}
+
+/// Prefixes `name` with the enclosing scope, if there is one.
+pub(crate) fn qualify_name(scope: &str, name: &str, separator: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}{}{}", scope, separator, name)
+    }
 }
 
+/// Functions declared inside one of these are listed as methods.
+pub(crate) fn is_type_kind(kind: &str) -> bool {
+    matches!(kind, "class" | "struct" | "enum" | "trait" | "interface" | "impl" | "object" | "protocol" | "extension" | "record" | "actor" | "given")
+}
+
+fn get_signature(node: Node, source_code: &str) -> String {
+    // Most grammars have a `body` field, the rest a body node of their own, and
+    // functions assigned to a name keep theirs on the value
+    let body = node.child_by_field_name("body")
+        .or_else(|| node.named_children(&mut node.walk())
+            .find(|child| child.kind().ends_with("body") || matches!(child.kind(), "compound_statement" | "do_block")))
+        .or_else(|| node.child_by_field_name("value").and_then(|value| value.child_by_field_name("body")));
+    let header = match body {
+        Some(body) => &source_code[node.start_byte()..body.start_byte()],
+        None => source_code[node.start_byte()..node.end_byte()].lines().next().unwrap_or(""),
+    };
+    clean_signature(header)
+}
+
+fn clean_signature(header: &str) -> String {
+    let mut signature = header.split_whitespace().collect::<Vec<&str>>().join(" ");
+    // Whatever introduces the body: `{`, `:`, `=`, `=>`, `do`
+    while let Some(stripped) = ["{", ":", ";", "=>", "=", " do"].iter().find_map(|suffix| signature.strip_suffix(suffix)) {
+        signature = stripped.trim_end().to_string();
+    }
+    signature
+}
+
+fn get_visibility(signature: &str) -> Option<String> {
+    if let Some(rest) = signature.strip_prefix("pub(") {
+        return rest.find(')').map(|end| format!("pub({})", &rest[..end]));
+    }
+    // Modifiers come before the parameters and base types, attribute arguments aside
+    signature.split_whitespace()
+        .take_while(|word| word.starts_with(['@', '[', '#']) || !(word.contains('(') || matches!(*word, ":" | "extends" | "implements")))
+        .find(|word| matches!(*word, "pub" | "public" | "private" | "protected" | "internal" | "fileprivate" | "open"))
+        .map(|word| word.to_string())
+}
+
+fn get_doc_summary(node: Node, source_code: &str) -> Option<String> {
+    // `export`, decorators and templates wrap the declaration the comment is attached to
+    let mut anchor = node;
+    while let Some(parent) = anchor.parent() {
+        let is_wrapper = matches!(parent.kind(), "export_statement" | "decorated_definition" | "template_declaration")
+            || (parent.start_position().row == anchor.start_position().row && anchor.prev_sibling().is_none());
+        if !is_wrapper || parent.parent().is_none() {
+            break;
+        }
+        anchor = parent;
+    }
+
+    let mut comments = Vec::new();
+    let mut next_row = anchor.start_position().row;
+    let mut current = anchor;
+    while let Some(previous) = current.prev_sibling() {
+        let is_adjacent = previous.end_position().row + 1 >= next_row;
+        if previous.kind().contains("comment") && is_adjacent {
+            comments.push(&source_code[previous.start_byte()..previous.end_byte()]);
+        } else if !(matches!(previous.kind(), "attribute_item" | "decorator" | "annotation" | "marker_annotation") && is_adjacent) {
+            break;
+        }
+        next_row = previous.start_position().row;
+        current = previous;
+    }
+    comments.reverse();
+
+    let text = comments.iter()
+        .flat_map(|comment| comment.lines())
+        .map(|line| line.trim()
+            .trim_start_matches(['/', '*', '#', '-', '%', ';', '!'])
+            .trim_end_matches("*/")
+            .trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ");
+    first_sentence(&text)
+}
+
+/// Text up to the first full stop, `None` for empty text.
+pub(crate) fn first_sentence(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    let end = text.find(". ").map(|i| i + 1).unwrap_or(text.len());
+    Some(text[..end].to_string())
+}
@@ -1,219 +1,359 @@
 use std::collections::HashMap;
-use rand::{thread_rng, Rng};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
 use std::future::Future;
 use std::pin::Pin;
-use tokio::io::AsyncWriteExt;
-use crate::code_segmenter::CodeSegmenter;
+use globset::GlobSet;
+use tokio::sync::Semaphore;
+use crate::code_segmenter::SegmenterFn;
+use crate::output_format::{FileRecord, OutputFormat, Record, TreeNode, get_files, render_records, render_symbol_index};
 
 async fn process_file(
     file_path: String,
     language_extensions: Arc<HashMap<String, String>>,
-    language_segmenters: Arc<HashMap<String, fn(String) -> Box<dyn CodeSegmenter>>>,
+    language_segmenters: Arc<HashMap<String, SegmenterFn>>,
     main_root: Arc<String>,
-    arch_dir: Arc<PathBuf>,
-    summary_file: Arc<tokio::sync::Mutex<tokio::fs::File>>,
-) {
+    arch_dir: Option<Arc<PathBuf>>,
+) -> Option<FileRecord> {
     let path = Path::new(&file_path);
-    if let Some(extension) = path.extension() {
-        if let Some(language) = language_extensions.get(extension.to_str().unwrap()) {
-            if let Some(segmenter_fn) = language_segmenters.get(language) {
-                match fs::read_to_string(&file_path).await {
-                    Ok(code) => {
-                        let segmenter = segmenter_fn(code);
-                        let simplified_code = segmenter.simplify_code();
-
-                        let main_root_path = Path::new(&*main_root);
-                        let save_path = arch_dir.join(path.strip_prefix(main_root_path).unwrap_or(&path));
-
-                        if let Err(e) = fs::create_dir_all(save_path.parent().unwrap()).await {
-                            eprintln!("Error creating directory: {}", e);
-                            return;
-                        }
-
-                        if let Err(e) = fs::write(&save_path, &simplified_code).await {
-                            eprintln!("Error writing file {}: {}", save_path.display(), e);
-                        }
-
-                        // Add content to summary file
-                        let relative_path = path.strip_prefix(main_root_path).unwrap_or(&path);
-                        let mut summary = summary_file.lock().await;
-                        if let Err(e) = summary.write_all(format!("\n{}\n\n", relative_path.display()).as_bytes()).await {
-                            eprintln!("Error writing to summary file: {}", e);
-                        }
-                        if let Err(e) = summary.write_all(simplified_code.as_bytes()).await {
-                            eprintln!("Error writing to summary file: {}", e);
-                        }
-                        if let Err(e) = summary.write_all(b"\n.................................................................\n").await {
-                            eprintln!("Error writing to summary file: {}", e);
-                        }
-                    }
-                    Err(e) => {
-                        // Log the error to _arch_xyzxyz/error.txt
-                        let error_path = arch_dir.join("error.txt");
-                        let error_message = format!("Error reading file {}: {}\n", file_path, e);
-
-                        if let Err(e) = fs::write(&error_path, error_message).await {
-                            eprintln!("Error writing to error log: {}", e);
-                        }
-                    }
-                }
-            }
+    let language = language_extensions.get(get_extension(path)?)?;
+    let segmenter_fn = language_segmenters.get(language)?;
+    let main_root_path = Path::new(&*main_root);
+    let relative_path = path.strip_prefix(main_root_path).unwrap_or(path);
+    let mut record = FileRecord {
+        path: relative_path.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        language: language.clone(),
+        segmenter: None,
+        text: String::new(),
+        symbols: Vec::new(),
+        source_bytes: 0,
+        source_lines: 0,
+        text_bytes: 0,
+        errors: Vec::new(),
+    };
+
+    let code = match fs::read_to_string(&file_path).await {
+        Ok(code) => code,
+        Err(e) => {
+            record.errors.push(format!("Error reading file {}: {}", file_path, e));
+            return Some(record);
+        }
+    };
+
+    record.source_bytes = code.len();
+    record.source_lines = code.lines().count();
+    let segmenter = segmenter_fn(code);
+    let simplified_code = segmenter.simplify_code();
+
+    if let Some(arch_dir) = arch_dir {
+        let save_path = arch_dir.join(relative_path);
+
+        if let Err(e) = fs::create_dir_all(save_path.parent().unwrap()).await {
+            record.errors.push(format!("Error creating directory: {}", e));
+            return Some(record);
+        }
+
+        if let Err(e) = fs::write(&save_path, &simplified_code).await {
+            record.errors.push(format!("Error writing file {}: {}", save_path.display(), e));
+        }
+    }
+
+    record.segmenter = Some(segmenter.name());
+    record.text_bytes = simplified_code.len();
+    record.text = simplified_code;
+    record.symbols = segmenter.extract_symbols();
+    Some(record)
+}
+
+fn get_extension(path: &Path) -> Option<&str> {
+    let file_name = path.file_name()?.to_str()?;
+    // Declaration files would otherwise be treated as regular `.ts` sources
+    if [".d.ts", ".d.mts", ".d.cts"].iter().any(|suffix| file_name.ends_with(suffix)) {
+        return Some("d.ts");
+    }
+    path.extension()?.to_str()
+}
+
+// Skip entries that start with '.' or match common generated and dependency directories
+fn is_skipped_entry(name: &str) -> bool {
+    name.starts_with('.')
+        || name == "node_modules"
+        || name == "target"
+        || name == "dist"
+        || name == "build"
+        || name == "vendor"
+        || name == "__pycache__"
+        || name == "logs"
+        || name == "coverage"
+        || name == "venv"
+        || name == "tmp"
+        || name == "temp"
+        || name == "cache"
+        || name == "Pods"
+        || name == "DerivedData"
+        || name == "bin"
+        || name == "pkg"
+        || name == "migrations"
+        || name == "CMakeFiles"
+        || name == "CMakeCache.txt"
+        || name == "Gemfile.lock"
+        || name == "composer.lock"
+        || name == "_build"
+        || name == "deps"
+}
+
+/// Which files are scanned, on top of the entries that are always skipped.
+#[derive(Default)]
+pub struct FileFilter {
+    /// Extensions as in `language_extensions`, any file when empty.
+    pub extensions: Vec<String>,
+    /// Matched against the path relative to the input, any file when `None`.
+    pub include: Option<GlobSet>,
+    pub exclude: Option<GlobSet>,
+}
+
+impl FileFilter {
+    /// Whether only some files are picked, rather than only some left out.
+    fn selects_files(&self) -> bool {
+        self.include.is_some() || !self.extensions.is_empty()
+    }
+
+    fn accepts_entry(&self, relative_path: &Path, is_dir: bool) -> bool {
+        if self.exclude.as_ref().is_some_and(|exclude| exclude.is_match(relative_path)) {
+            return false;
+        }
+        if is_dir {
+            return true;
         }
+        self.include.as_ref().is_none_or(|include| include.is_match(relative_path))
+            && (self.extensions.is_empty() || get_extension(relative_path).is_some_and(|extension| self.extensions.iter().any(|e| e == extension)))
     }
 }
 
+/// Settings shared by every command that walks the input.
+pub struct ScanOptions {
+    pub filter: FileFilter,
+    /// Number of files processed at once.
+    pub concurrency: usize,
+    pub verbosity: Verbosity,
+}
 
-fn generate_directory_tree<'a>(
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    /// Only errors that stop the run
+    Quiet,
+    /// Also errors in single files
+    Normal,
+    /// Also every processed file
+    Verbose,
+}
+
+/// Settings of the `summarize` command.
+pub struct SummaryOptions {
+    pub output_dir: PathBuf,
+    pub overwrite: bool,
+    pub write_index: bool,
+    pub format: OutputFormat,
+}
+
+/// The directory tree with the skipped and filtered entries left out, children sorted by name.
+pub async fn collect_directory_tree(
+    main_root: &Path,
+    arch_dir: Option<&Path>,
+    filter: &FileFilter,
+) -> TreeNode {
+    collect_directory_node(main_root, main_root, arch_dir, filter).await
+}
+
+fn collect_directory_node<'a>(
     path: &'a Path,
-    prefix: &'a str,
-    is_last: bool,
-) -> Pin<Box<dyn Future<Output = String> + 'a>> {
+    main_root: &'a Path,
+    arch_dir: Option<&'a Path>,
+    filter: &'a FileFilter,
+) -> Pin<Box<dyn Future<Output = TreeNode> + 'a>> {
     Box::pin(async move {
-        let mut result = String::new();
-        let entry_prefix = if is_last { "└── " } else { "├── " };
-        let mut entry = prefix.to_string();
-        entry.push_str(entry_prefix);
-        entry.push_str(path.file_name().unwrap().to_str().unwrap());
-        result.push_str(&entry);
-        result.push('\n');
-
-        if path.is_dir() {
-            let mut entries = Vec::new();
-            if let Ok(mut read_dir) = fs::read_dir(path).await {
-                while let Ok(Some(entry)) = read_dir.next_entry().await {
-                    let entry_name = entry.file_name();
-                    // Convert OsString to &str for easier comparison
-                    let entry_name_str = entry_name.to_str().unwrap_or("");
-
-                    // Skip directories that start with '.' or match common directories
-                    if entry_name_str.starts_with('.')
-                    || entry_name_str == "node_modules"
-                    || entry_name_str == "target"
-                    || entry_name_str == "dist"
-                    || entry_name_str == "build"
-                    || entry_name_str == "vendor"
-                    || entry_name_str == "__pycache__"
-                    || entry_name_str == "logs"
-                    || entry_name_str == "coverage"
-                    || entry_name_str == "venv"
-                    || entry_name_str == "tmp"
-                    || entry_name_str == "temp"
-                    || entry_name_str == "cache"
-                    || entry_name_str == "Pods"
-                    || entry_name_str == "DerivedData"
-                    || entry_name_str == "bin"
-                    || entry_name_str == "pkg"
-                    || entry_name_str == "migrations"
-                    || entry_name_str == "CMakeFiles"
-                    || entry_name_str == "CMakeCache.txt"
-                    || entry_name_str == "Gemfile.lock"
-                    || entry_name_str == "composer.lock"
-                    || entry_name_str == "_build"
-                    || entry_name_str == "deps"
-                {
-                    continue;
-                }
+        let name = path.file_name().unwrap().to_str().unwrap().to_string();
+        if !path.is_dir() {
+            return TreeNode { name, kind: "file", children: None };
+        }
 
+        let mut entries = Vec::new();
+        if let Ok(mut read_dir) = fs::read_dir(path).await {
+            while let Ok(Some(entry)) = read_dir.next_entry().await {
+                let entry_path = entry.path();
+                let relative_path = entry_path.strip_prefix(main_root).unwrap_or(&entry_path);
+                // The output directory, and those of earlier runs, may well be inside the input
+                if !is_skipped_entry(entry.file_name().to_str().unwrap_or(""))
+                    && Some(entry_path.as_path()) != arch_dir
+                    && !is_previous_output(&entry_path)
+                    && filter.accepts_entry(relative_path, entry_path.is_dir())
+                {
                     entries.push(entry);
                 }
             }
-            entries.sort_by_key(|a| a.file_name());
-            let count = entries.len();
-            for (i, entry) in entries.into_iter().enumerate() {
-                let child_prefix = if is_last { "    " } else { "│   " };
-                let child_path = entry.path();
-                let child_tree = generate_directory_tree(
-                    &child_path,
-                    &format!("{}{}", prefix, child_prefix),
-                    i == count - 1,
-                ).await;
-                result.push_str(&child_tree);
+        }
+        entries.sort_by_key(|a| a.file_name());
+        let mut children = Vec::new();
+        for entry in entries {
+            let child = collect_directory_node(&entry.path(), main_root, arch_dir, filter).await;
+            // Directories left without any of the picked files are noise
+            if filter.selects_files() && child.children.as_ref().is_some_and(|children| children.is_empty()) {
+                continue;
             }
+            children.push(child);
         }
-
-        result
+        TreeNode { name, kind: "directory", children: Some(children) }
     })
 }
 
+/// Files in the order they appear in the tree.
+fn get_file_paths(node: &TreeNode, path: &Path, file_paths: &mut Vec<PathBuf>) {
+    match &node.children {
+        Some(children) => {
+            for child in children {
+                get_file_paths(child, &path.join(&child.name), file_paths);
+            }
+        },
+        None => file_paths.push(path.to_path_buf()),
+    }
+}
+
+/// Processes the files of the tree in parallel and returns their records in tree order.
+/// The simplified files are only written when there is an `arch_dir`.
+pub async fn process_files(
+    main_root: &Path,
+    tree: &TreeNode,
+    language_extensions: Arc<HashMap<String, String>>,
+    language_segmenters: Arc<HashMap<String, SegmenterFn>>,
+    arch_dir: Option<Arc<PathBuf>>,
+    options: &ScanOptions,
+) -> Vec<FileRecord> {
+    let mut file_paths = Vec::new();
+    get_file_paths(tree, main_root, &mut file_paths);
+    let main_root = Arc::new(main_root.to_str().unwrap().to_string());
+    let permits = Arc::new(Semaphore::new(options.concurrency.max(1)));
+
+    let mut tasks = Vec::new();
+    for path in file_paths {
+        let file_path = path.to_str().unwrap().to_string();
+        let language_extensions = Arc::clone(&language_extensions);
+        let language_segmenters = Arc::clone(&language_segmenters);
+        let main_root = Arc::clone(&main_root);
+        let arch_dir = arch_dir.clone();
+        let permits = Arc::clone(&permits);
+        tasks.push(tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await.unwrap();
+            process_file(
+                file_path,
+                language_extensions,
+                language_segmenters,
+                main_root,
+                arch_dir,
+            ).await
+        }));
+    }
+
+    let mut records = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(Some(record)) => {
+                if options.verbosity >= Verbosity::Verbose {
+                    eprintln!("{} ({}, {} symbols)", record.path, record.language, record.symbols.len());
+                }
+                if options.verbosity >= Verbosity::Normal {
+                    for error in &record.errors {
+                        eprintln!("{}", error);
+                    }
+                }
+                records.push(record);
+            },
+            Ok(None) => {},
+            Err(e) => eprintln!("Task failed: {}", e),
+        }
+    }
+    records
+}
+
+/// Whether `dir` has a summary in it, in any of the formats.
+fn is_previous_output(dir: &Path) -> bool {
+    [OutputFormat::Text, OutputFormat::Json, OutputFormat::Jsonl, OutputFormat::Markdown].iter()
+        .any(|format| dir.join(format.summary_file_name()).is_file())
+}
 
+/// Writes the simplified files and the summary, returns the output directory.
 pub async fn main_parser(
     directory_path: String,
     language_extensions: HashMap<String, String>,
-    language_segmenters: HashMap<String, fn(String) -> Box<dyn CodeSegmenter>>,
-) {
+    language_segmenters: HashMap<String, SegmenterFn>,
+    scan_options: &ScanOptions,
+    summary_options: SummaryOptions,
+) -> Result<PathBuf, String> {
     let language_extensions = Arc::new(language_extensions);
     let language_segmenters = Arc::new(language_segmenters);
-    let main_root = Arc::new(directory_path);
-
-    // Generate random suffix once for the entire process
-    let random_suffix: String = thread_rng()
-        .sample_iter(&rand::distributions::Alphanumeric)
-        .take(6)
-        .map(char::from)
-        .collect();
+    let main_root_path = Path::new(&directory_path);
+    let output_dir = summary_options.output_dir;
+    let format = summary_options.format;
 
-    let main_root_path = Path::new(&*main_root);
-    let arch_dir = Arc::new(main_root_path.parent().unwrap().join(format!("_arch_{}", random_suffix)));
+    // A previous run is only replaced when asked to, and never if it holds the input.
+    // One inside the input is left out of the tree, so replacing it is fine
+    if output_dir.exists() {
+        if !summary_options.overwrite {
+            return Err(format!("Output directory {} already exists, pass --overwrite to replace it", output_dir.display()));
+        }
+        let existing_dir = output_dir.canonicalize().map_err(|e| format!("Error reading output directory: {}", e))?;
+        if main_root_path.starts_with(&existing_dir) {
+            return Err(format!("Refusing to overwrite {} as it contains the input directory", existing_dir.display()));
+        }
+        if !is_previous_output(&existing_dir) {
+            return Err(format!("Refusing to overwrite {} as it holds no summary from a previous run", existing_dir.display()));
+        }
+        fs::remove_dir_all(&existing_dir).await.map_err(|e| format!("Error removing output directory: {}", e))?;
+    }
 
     // Create the arch_dir once
-    if let Err(e) = fs::create_dir_all(&*arch_dir).await {
-        eprintln!("Error creating arch directory: {}", e);
-        return;
-    }
+    fs::create_dir_all(&output_dir).await.map_err(|e| format!("Error creating arch directory: {}", e))?;
+    let arch_dir = Arc::new(output_dir.canonicalize().unwrap_or(output_dir));
 
-    // Create and initialize summary file
-    let summary_path = arch_dir.join("summary.txt");
-    let summary_file = Arc::new(tokio::sync::Mutex::new(
-        fs::File::create(&summary_path).await.unwrap()
-    ));
-
-    // Generate and write directory tree
-    let tree = generate_directory_tree(main_root_path, "", true).await;
-    let mut summary = summary_file.lock().await;
-    if let Err(e) = summary.write_all(tree.as_bytes()).await {
-        eprintln!("Error writing directory tree to summary file: {}", e);
-    }
-    if let Err(e) = summary.write_all(b"\n.................................................................\n").await {
-        eprintln!("Error writing separator to summary file: {}", e);
-    }
-    drop(summary);  // Release the lock
+    let tree = collect_directory_tree(main_root_path, Some(&arch_dir), &scan_options.filter).await;
+    let files = process_files(
+        main_root_path,
+        &tree,
+        language_extensions,
+        language_segmenters,
+        Some(Arc::clone(&arch_dir)),
+        scan_options,
+    ).await;
+    let mut records = vec![Record::Header {
+        root: tree.name.clone(),
+        tree,
+    }];
+    records.extend(files.into_iter().map(Record::File));
 
-    let mut tasks = Vec::new();
-    let mut stack = vec![PathBuf::from(&*main_root)];
-
-    while let Some(current_dir) = stack.pop() {
-        let mut entries = fs::read_dir(&current_dir).await.unwrap();
-        while let Some(entry) = entries.next_entry().await.unwrap() {
-            let path = entry.path();
-            if path.is_dir() {
-                stack.push(path);
-            } else if path.is_file() {
-                let file_path = path.to_str().unwrap().to_string();
-                let language_extensions = Arc::clone(&language_extensions);
-                let language_segmenters = Arc::clone(&language_segmenters);
-                let main_root = Arc::clone(&main_root);
-                let arch_dir = Arc::clone(&arch_dir);
-                let summary_file = Arc::clone(&summary_file);
-                tasks.push(tokio::spawn(async move {
-                    process_file(
-                        file_path,
-                        language_extensions,
-                        language_segmenters,
-                        main_root,
-                        arch_dir,
-                        summary_file,
-                    ).await;
-                }));
-            }
-        }
+    let summary_path = arch_dir.join(format.summary_file_name());
+    let rendered = render_records(&records, format).map_err(|e| format!("Error serialising summary: {}", e))?;
+    fs::write(&summary_path, rendered).await.map_err(|e| format!("Error writing to summary file: {}", e))?;
+
+    let files = get_files(&records);
+
+    // Symbols of every file, only written when asked for
+    if summary_options.write_index {
+        fs::write(arch_dir.join("index.txt"), render_symbol_index(&files)).await
+            .map_err(|e| format!("Error writing index file: {}", e))?;
     }
 
-    for task in tasks {
-        if let Err(e) = task.await {
-            eprintln!("Task failed: {}", e);
+    // Log the errors to _arch_xyzxyz/error.txt
+    let error_log: String = files.iter()
+        .flat_map(|file| file.errors.iter())
+        .map(|error| format!("{}\n", error))
+        .collect();
+    if !error_log.is_empty() {
+        if let Err(e) = fs::write(arch_dir.join("error.txt"), error_log).await {
+            eprintln!("Error writing to error log: {}", e);
         }
     }
-}
\ No newline at end of file
+    Ok(arch_dir.to_path_buf())
+}
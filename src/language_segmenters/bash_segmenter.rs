@@ -0,0 +1,141 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_bash::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct BashSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for BashSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func(&mut cursor, "", false)
+    }
+}
+
+impl BashSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Bash grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Bash code");
+
+        Box::new(BashSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "program" => self.process_program(cursor),
+            "command" => self.process_command(cursor),
+            "declaration_command" => self.process_declaration(cursor),
+            "function_definition" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_program(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_command(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let command_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        // Sourced files are the script's dependencies
+        match command_name.as_str() {
+            "source" | "." => self.get_node_text(node),
+            _ => String::new(),
+        }
+    }
+
+    fn process_declaration(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let text = self.get_node_text(node);
+        if text.starts_with("export") {
+            text
+        } else {
+            String::new()
+        }
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{} {{ ... }}", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].trim_end().to_string()
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        match node.kind() {
+            "function_definition" => Some(("function", self.get_node_text(node.child_by_field_name("name")?))),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_c::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct CSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for CSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_type(&mut cursor, "", false)
+    }
+}
+
+impl CSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading C grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse C code");
+
+        Box::new(CSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "translation_unit" => self.process_children(cursor),
+            "preproc_if" | "preproc_ifdef" | "preproc_else" | "preproc_elif" => self.process_children(cursor),
+            "preproc_include" | "preproc_def" => self.get_node_text(node).trim_end().to_string(),
+            "preproc_function_def" => self.process_macro_function(cursor),
+            "type_definition" => self.get_node_text(node),
+            "struct_specifier" | "union_specifier" | "enum_specifier" => format!("{};", self.get_node_text(node)),
+            "declaration" => self.process_declaration(cursor),
+            "function_definition" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_macro_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let macro_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "UNNAMED".to_string());
+        let params = node.child_by_field_name("parameters")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "()".to_string());
+
+        format!("#define {}{}", macro_name, params)
+    }
+
+    fn process_declaration(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let declarators: Vec<Node> = node.children_by_field_name("declarator", &mut node.walk()).collect();
+
+        if !declarators.iter().any(|d| d.kind() == "init_declarator") {
+            return self.get_node_text(node);
+        }
+
+        // Drop initialisers so global tables don't dump their contents
+        let specifiers = self.source_code[node.start_byte()..declarators[0].start_byte()].trim_end();
+        let names: Vec<String> = declarators.iter()
+            .map(|d| match d.kind() {
+                "init_declarator" => d.child_by_field_name("declarator")
+                    .map(|n| self.get_node_text(n))
+                    .unwrap_or_default(),
+                _ => self.get_node_text(*d),
+            })
+            .collect();
+
+        format!("{} {};", specifiers, names.join(", "))
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{};", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].trim_end().to_string()
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_type(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_type(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_type(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        match node.kind() {
+            "function_definition" => Some(("function", self.get_declarator_name(node)?)),
+            "struct_specifier" | "union_specifier" | "enum_specifier" if node.child_by_field_name("body").is_some() => {
+                let type_name = node.child_by_field_name("name")
+                    .map(|n| self.get_node_text(n))
+                    .unwrap_or_else(|| "<anonymous>".to_string());
+                Some((node.kind().trim_end_matches("_specifier"), type_name))
+            },
+            _ => None,
+        }
+    }
+
+    fn get_declarator_name(&self, node: Node) -> Option<String> {
+        // Pointer, reference and function declarators wrap the name itself
+        let mut declarator = node.child_by_field_name("declarator")?;
+        while !declarator.kind().ends_with("identifier") && !declarator.kind().ends_with("_name") {
+            declarator = declarator.child_by_field_name("declarator")
+                .or_else(|| declarator.named_child(declarator.named_child_count().checked_sub(1)?))?;
+        }
+        Some(self.get_node_text(declarator))
+    }
+}
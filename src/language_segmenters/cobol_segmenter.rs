@@ -0,0 +1,335 @@
+use std::fmt::Write;
+use crate::code_segmenter::{CodeSegmenter, Symbol, qualify_name};
+
+// No COBOL grammar builds against our tree-sitter version, and the language is
+// rigidly laid out in divisions anyway, so it is reduced line by line.
+pub struct CobolSegmenter {
+    source_code: String,
+}
+
+#[derive(PartialEq)]
+enum Division {
+    Identification,
+    Environment,
+    Data,
+    Procedure,
+}
+
+struct CodeLine {
+    line_number: usize,
+    text: String,
+    // Area A (columns 8-11) is where division, section, paragraph and 01 level names start
+    in_area_a: bool,
+}
+
+impl CodeSegmenter for CobolSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut result = String::new();
+        let lines = self.code_lines();
+        // Copybooks are bare record layouts without any division headers
+        let mut division = if lines.iter().any(|line| self.get_division(&line.text.trim().to_ascii_uppercase()).is_some()) {
+            Division::Identification
+        } else {
+            Division::Data
+        };
+        let mut data_entry = String::new();
+        let mut level_stack: Vec<u32> = Vec::new();
+        let mut in_section = false;
+        let mut in_paragraph = false;
+
+        for line in lines {
+            let upper = line.text.trim().to_ascii_uppercase();
+
+            if let Some(next_division) = self.get_division(&upper) {
+                division = next_division;
+                in_section = false;
+                in_paragraph = false;
+                writeln!(&mut result, "{}", upper).unwrap();
+                continue;
+            }
+
+            if let Some(program_id) = self.get_program_id(&upper) {
+                writeln!(&mut result, "PROGRAM-ID. {}.", program_id).unwrap();
+                continue;
+            }
+
+            match division {
+                Division::Data => {
+                    // Entries can span lines, they only end at a period
+                    data_entry.push(' ');
+                    data_entry.push_str(&upper);
+                    if upper.ends_with('.') {
+                        let entry = self.process_data_entry(data_entry.trim(), &mut level_stack);
+                        if !entry.is_empty() {
+                            writeln!(&mut result, "{}", entry).unwrap();
+                        }
+                        data_entry.clear();
+                    }
+                },
+                Division::Procedure => {
+                    if line.in_area_a {
+                        if let Some(section) = self.get_section_name(&upper) {
+                            writeln!(&mut result, "{} SECTION.", section).unwrap();
+                            in_section = true;
+                            in_paragraph = false;
+                            continue;
+                        }
+                        if let Some(paragraph) = self.get_paragraph_name(&upper) {
+                            let indent = if in_section { "    " } else { "" };
+                            writeln!(&mut result, "{}{}.", indent, paragraph).unwrap();
+                            in_paragraph = true;
+                            continue;
+                        }
+                    }
+
+                    let depth = usize::from(in_section) + usize::from(in_paragraph);
+                    for reference in self.get_references(&upper) {
+                        writeln!(&mut result, "{}{}", "    ".repeat(depth), reference).unwrap();
+                    }
+                },
+                Division::Identification | Division::Environment => {
+                    for reference in self.get_references(&upper) {
+                        writeln!(&mut result, "{}", reference).unwrap();
+                    }
+                },
+            }
+        }
+
+        result.trim_end().to_string()
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let lines = self.code_lines();
+        let mut entries: Vec<(&str, String, String, usize, usize)> = Vec::new();
+        let mut division = Division::Identification;
+        // Indices of the entries still waiting for their last line
+        let mut program: Option<usize> = None;
+        let mut section: Option<usize> = None;
+        let mut paragraph: Option<usize> = None;
+        let mut previous_line = 0;
+
+        for line in &lines {
+            let upper = line.text.trim().to_ascii_uppercase();
+            let line_number = line.line_number + 1;
+
+            if let Some(next_division) = self.get_division(&upper) {
+                division = next_division;
+            } else if let Some(program_id) = self.get_program_id(&upper) {
+                for index in [program, section, paragraph].into_iter().flatten() {
+                    entries[index].4 = previous_line;
+                }
+                (section, paragraph) = (None, None);
+                program = Some(entries.len());
+                entries.push(("program", program_id.clone(), program_id, line_number, line_number));
+            } else if division == Division::Procedure && line.in_area_a {
+                let scope = [section, program].into_iter().flatten().next()
+                    .map(|index| entries[index].2.clone())
+                    .unwrap_or_default();
+                if let Some(section_name) = self.get_section_name(&upper) {
+                    for index in [section, paragraph].into_iter().flatten() {
+                        entries[index].4 = previous_line;
+                    }
+                    let program_name = program.map(|index| entries[index].2.clone()).unwrap_or_default();
+                    paragraph = None;
+                    section = Some(entries.len());
+                    entries.push(("section", section_name.clone(), qualify_name(&program_name, &section_name, "."), line_number, line_number));
+                } else if let Some(paragraph_name) = self.get_paragraph_name(&upper) {
+                    if let Some(index) = paragraph {
+                        entries[index].4 = previous_line;
+                    }
+                    paragraph = Some(entries.len());
+                    entries.push(("paragraph", paragraph_name.clone(), qualify_name(&scope, &paragraph_name, "."), line_number, line_number));
+                }
+            }
+            previous_line = line_number;
+        }
+
+        for index in [program, section, paragraph].into_iter().flatten() {
+            entries[index].4 = previous_line;
+        }
+
+        // Paragraphs nest in their section, sections in their program
+        let mut symbols: Vec<Symbol> = Vec::new();
+        for (kind, name, qualified_name, start_line, end_line) in entries {
+            let mut symbol = Symbol::from_lines(kind, &name, &qualified_name, start_line, end_line, &self.source_code);
+            // The source lines carry sequence numbers and lower case spellings
+            symbol.signature = match kind {
+                "program" => format!("PROGRAM-ID. {}.", name),
+                "section" => format!("{} SECTION.", name),
+                _ => format!("{}.", name),
+            };
+            let mut parent = match kind {
+                "program" => None,
+                _ => symbols.last_mut().filter(|last| last.kind == "program" && last.end_line >= start_line),
+            };
+            if kind == "paragraph" {
+                if let Some(program) = parent.take() {
+                    let in_section = program.children.last().is_some_and(|last| last.kind == "section" && last.end_line >= start_line);
+                    parent = if in_section { program.children.last_mut() } else { Some(program) };
+                }
+            }
+            match parent {
+                Some(parent) => parent.children.push(symbol),
+                None => symbols.push(symbol),
+            }
+        }
+        symbols
+    }
+}
+
+impl CobolSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        Box::new(CobolSegmenter {
+            source_code: code,
+        })
+    }
+
+    fn code_lines(&self) -> Vec<CodeLine> {
+        let fixed_format = self.is_fixed_format();
+        let mut lines = Vec::new();
+
+        for (line_number, line) in self.source_code.lines().enumerate() {
+            let text: String = if fixed_format {
+                // Columns 1-6 are sequence numbers, 7 the indicator and 73+ identification
+                match line.chars().nth(6) {
+                    Some('*' | '/' | 'D' | 'd') => continue,
+                    _ => line.chars().take(72).skip(7).collect(),
+                }
+            } else {
+                line.to_string()
+            };
+
+            // Floating comments
+            let text = match text.find("*>") {
+                Some(comment_start) => text[..comment_start].to_string(),
+                None => text,
+            };
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            let in_area_a = text.len() - text.trim_start().len() < 4;
+            lines.push(CodeLine {
+                line_number,
+                text: text.trim_end().to_string(),
+                in_area_a,
+            });
+        }
+
+        lines
+    }
+
+    fn is_fixed_format(&self) -> bool {
+        self.source_code.lines()
+            .filter(|line| !line.trim().is_empty())
+            .all(|line| line.chars().take(6).all(|c| c.is_ascii_digit() || c == ' '))
+    }
+
+    fn get_division(&self, upper: &str) -> Option<Division> {
+        let words: Vec<&str> = upper.split_whitespace().collect();
+        match words.as_slice() {
+            ["IDENTIFICATION" | "ID", division, ..] if division.starts_with("DIVISION") => Some(Division::Identification),
+            ["ENVIRONMENT", division, ..] if division.starts_with("DIVISION") => Some(Division::Environment),
+            ["DATA", division, ..] if division.starts_with("DIVISION") => Some(Division::Data),
+            ["PROCEDURE", division, ..] if division.starts_with("DIVISION") => Some(Division::Procedure),
+            _ => None,
+        }
+    }
+
+    fn get_program_id(&self, upper: &str) -> Option<String> {
+        let rest = upper.strip_prefix("PROGRAM-ID")?.trim_start_matches('.').trim();
+        let program_id = rest.split_whitespace().next()?;
+        Some(program_id.trim_end_matches('.').trim_matches(|c| c == '\'' || c == '"').to_string())
+    }
+
+    fn get_section_name(&self, upper: &str) -> Option<String> {
+        match upper.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            [name, "SECTION."] => Some(name.to_string()),
+            _ => None,
+        }
+    }
+
+    fn get_paragraph_name(&self, upper: &str) -> Option<String> {
+        let name = upper.strip_suffix('.')?;
+        let is_statement = name.starts_with("END-")
+            || matches!(name, "EXIT" | "GOBACK" | "CONTINUE" | "DECLARATIVES" | "ELSE");
+        if name.is_empty() || name.contains(char::is_whitespace) || is_statement {
+            None
+        } else {
+            Some(name.to_string())
+        }
+    }
+
+    fn get_references(&self, upper: &str) -> Vec<String> {
+        let words: Vec<&str> = upper.split_whitespace().collect();
+        words.windows(2)
+            .filter_map(|pair| match pair {
+                ["CALL", target] => Some(format!("CALL {}", target.trim_end_matches('.'))),
+                ["COPY", copybook] => Some(format!("COPY {}.", copybook.trim_end_matches('.'))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn process_data_entry(&self, entry: &str, level_stack: &mut Vec<u32>) -> String {
+        let words: Vec<&str> = entry.trim_end_matches('.').split_whitespace().collect();
+        let level = match words.first().and_then(|word| word.parse::<u32>().ok()) {
+            Some(level) => level,
+            None => {
+                // FD/SD entries, section headers and copybooks
+                return match words.as_slice() {
+                    ["FD" | "SD", name, ..] => format!("{} {}.", words[0], name),
+                    [_, "SECTION"] => entry.to_string(),
+                    ["COPY", copybook, ..] => format!("COPY {}.", copybook),
+                    _ => String::new(),
+                };
+            },
+        };
+
+        // Condition names and RENAMES don't describe storage
+        if level == 66 || level == 88 {
+            return String::new();
+        }
+        if level == 1 || level == 77 {
+            level_stack.clear();
+        }
+        while level_stack.last().is_some_and(|&parent| parent >= level) {
+            level_stack.pop();
+        }
+        let depth = level_stack.len();
+        level_stack.push(level);
+
+        let mut description = format!("{:02}", level);
+        let mut rest = &words[1..];
+        match rest.first() {
+            Some(name) if !self.is_clause_keyword(name) => {
+                description.push(' ');
+                description.push_str(name);
+                rest = &rest[1..];
+            },
+            _ => description.push_str(" FILLER"),
+        }
+
+        for (i, word) in rest.iter().enumerate() {
+            match *word {
+                "PIC" | "PICTURE" | "OCCURS" | "REDEFINES" => {
+                    let clause_value = rest[i + 1..].iter().find(|value| **value != "IS");
+                    if let Some(clause_value) = clause_value {
+                        let keyword = if *word == "PICTURE" { "PIC" } else { word };
+                        write!(&mut description, " {} {}", keyword, clause_value).unwrap();
+                    }
+                },
+                usage if usage.starts_with("COMP") || usage == "BINARY" || usage == "PACKED-DECIMAL" => {
+                    write!(&mut description, " {}", usage).unwrap();
+                },
+                _ => {},
+            }
+        }
+
+        format!("{}{}.", "    ".repeat(depth), description)
+    }
+
+    fn is_clause_keyword(&self, word: &str) -> bool {
+        matches!(word, "PIC" | "PICTURE" | "OCCURS" | "REDEFINES" | "VALUE" | "VALUES" | "USAGE" | "COMP" | "COMP-3")
+    }
+}
@@ -0,0 +1,282 @@
+use std::fmt::Write;
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_cpp::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct CppSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for CppSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
+    }
+}
+
+impl CppSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading C++ grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse C++ code");
+
+        Box::new(CppSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "translation_unit" => self.process_children(cursor),
+            "preproc_if" | "preproc_ifdef" | "preproc_else" | "preproc_elif" => self.process_children(cursor),
+            "preproc_include" | "preproc_def" => self.get_node_text(node).trim_end().to_string(),
+            "preproc_function_def" => self.process_macro_function(cursor),
+            "namespace_definition" => self.process_namespace(cursor),
+            "linkage_specification" => self.process_linkage(cursor),
+            "template_declaration" => self.process_template(cursor),
+            "class_specifier" | "struct_specifier" | "union_specifier" => self.process_class(cursor),
+            "enum_specifier" => format!("{};", self.get_node_text(node)),
+            "type_definition" | "alias_declaration" | "using_declaration" | "namespace_alias_definition" => self.get_node_text(node),
+            "declaration" => self.process_declaration(cursor),
+            "function_definition" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_macro_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let macro_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "UNNAMED".to_string());
+        let params = node.child_by_field_name("parameters")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "()".to_string());
+
+        format!("#define {}{}", macro_name, params)
+    }
+
+    fn process_namespace(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let header = match node.child_by_field_name("name") {
+            Some(name) => format!("namespace {}", self.get_node_text(name)),
+            None => "namespace".to_string(),
+        };
+
+        let mut namespace_def = format!("{} {{\n", header);
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut body_cursor = body.walk();
+            let body_text = self.process_children(&mut body_cursor);
+            namespace_def.push_str(&body_text.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+        namespace_def.push('}');
+        namespace_def
+    }
+
+    fn process_linkage(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+        let header = self.source_code[node.start_byte()..body.start_byte()].trim_end();
+
+        let mut body_cursor = body.walk();
+        if body.kind() != "declaration_list" {
+            return format!("{} {}", header, self.process_node(&mut body_cursor));
+        }
+
+        let mut linkage_def = format!("{} {{\n", header);
+        let body_text = self.process_children(&mut body_cursor);
+        linkage_def.push_str(&body_text.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        linkage_def.push('}');
+        linkage_def
+    }
+
+    fn process_template(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let header_end = node.child_by_field_name("parameters")
+            .map(|p| p.end_byte())
+            .unwrap_or_else(|| node.start_byte());
+        let header = &self.source_code[node.start_byte()..header_end];
+
+        let declaration = node.named_children(&mut node.walk())
+            .filter(|child| child.kind() != "template_parameter_list")
+            .last();
+        match declaration {
+            Some(declaration) => {
+                let mut declaration_cursor = declaration.walk();
+                format!("{}\n{}", header, self.process_node(&mut declaration_cursor))
+            },
+            None => header.to_string(),
+        }
+    }
+
+    fn process_class(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return format!("{};", self.get_node_text(node)),
+        };
+
+        // Keeps the class key, name and base clause
+        let header = self.source_code[node.start_byte()..body.start_byte()].trim_end();
+        let mut class_def = format!("{} {{\n", header);
+
+        for child in body.named_children(&mut body.walk()) {
+            match child.kind() {
+                "access_specifier" => {
+                    writeln!(class_def, "{}:", self.get_node_text(child)).unwrap();
+                },
+                "field_declaration" if child.child_by_field_name("declarator").is_none() => {
+                    // Nested type definitions are parsed as declarator-less fields
+                    let member = match child.child_by_field_name("type") {
+                        Some(nested) if nested.child_by_field_name("body").is_some() => {
+                            let mut nested_cursor = nested.walk();
+                            self.process_node(&mut nested_cursor)
+                        },
+                        _ => self.get_node_text(child),
+                    };
+                    class_def.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+                },
+                "field_declaration" | "declaration" | "friend_declaration" | "alias_declaration"
+                | "using_declaration" | "type_definition" | "static_assert_declaration" => {
+                    writeln!(class_def, "    {}", self.get_node_text(child)).unwrap();
+                },
+                "function_definition" | "template_declaration" => {
+                    let mut child_cursor = child.walk();
+                    let member = self.process_node(&mut child_cursor);
+                    class_def.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+                },
+                _ => {}
+            }
+        }
+
+        class_def.push_str("};");
+        class_def
+    }
+
+    fn process_declaration(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let declarators: Vec<Node> = node.children_by_field_name("declarator", &mut node.walk()).collect();
+
+        if !declarators.iter().any(|d| d.kind() == "init_declarator") {
+            return self.get_node_text(node);
+        }
+
+        // Drop initialisers so global tables don't dump their contents
+        let specifiers = self.source_code[node.start_byte()..declarators[0].start_byte()].trim_end();
+        let names: Vec<String> = declarators.iter()
+            .map(|d| match d.kind() {
+                "init_declarator" => d.child_by_field_name("declarator")
+                    .map(|n| self.get_node_text(n))
+                    .unwrap_or_default(),
+                _ => self.get_node_text(*d),
+            })
+            .collect();
+
+        format!("{} {};", specifiers, names.join(", "))
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{};", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Stop before constructor initialiser lists as well as the body
+        let end = node.children(&mut node.walk())
+            .find(|child| child.kind() == "field_initializer_list" || child.kind() == "compound_statement")
+            .or_else(|| node.child_by_field_name("body"))
+            .map(|child| child.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let signature = self.source_code[node.start_byte()..end].trim_end();
+        signature.trim_end_matches(';').trim_end().to_string()
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, "::");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        match node.kind() {
+            "function_definition" => Some(("function", self.get_declarator_name(node)?)),
+            "class_specifier" | "struct_specifier" | "union_specifier" | "enum_specifier" if node.child_by_field_name("body").is_some() => {
+                let type_name = node.child_by_field_name("name")
+                    .map(|n| self.get_node_text(n))
+                    .unwrap_or_else(|| "<anonymous>".to_string());
+                Some((node.kind().trim_end_matches("_specifier"), type_name))
+            },
+            "namespace_definition" => Some(("namespace", self.get_node_text(node.child_by_field_name("name")?))),
+            _ => None,
+        }
+    }
+
+    fn get_declarator_name(&self, node: Node) -> Option<String> {
+        // Pointer, reference and function declarators wrap the name itself
+        let mut declarator = node.child_by_field_name("declarator")?;
+        while !declarator.kind().ends_with("identifier") && !declarator.kind().ends_with("_name") {
+            declarator = declarator.child_by_field_name("declarator")
+                .or_else(|| declarator.named_child(declarator.named_child_count().checked_sub(1)?))?;
+        }
+        Some(self.get_node_text(declarator))
+    }
+}
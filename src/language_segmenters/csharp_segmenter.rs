@@ -0,0 +1,220 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_c_sharp::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct CSharpSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for CSharpSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
+    }
+}
+
+impl CSharpSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading C# grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse C# code");
+
+        Box::new(CSharpSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "compilation_unit" => self.process_children(cursor),
+            "using_directive" | "extern_alias_directive" => self.get_node_text(node),
+            "file_scoped_namespace_declaration" => self.process_file_scoped_namespace(cursor),
+            "namespace_declaration" => self.process_namespace(cursor),
+            "class_declaration" | "struct_declaration" | "record_declaration"
+            | "record_struct_declaration" | "interface_declaration" => self.process_type(cursor),
+            "enum_declaration" | "delegate_declaration" => self.get_node_text(node),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_file_scoped_namespace(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let namespace_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        // The namespace's members are siblings of its name in the tree
+        let members = self.process_children(cursor);
+        format!("namespace {};\n{}", namespace_name, members)
+    }
+
+    fn process_namespace(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let namespace_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        let mut namespace_def = format!("namespace {} {{\n", namespace_name);
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut body_cursor = body.walk();
+            let body_text = self.process_children(&mut body_cursor);
+            namespace_def.push_str(&body_text.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+        namespace_def.push('}');
+        namespace_def
+    }
+
+    fn process_type(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) if body.kind() == "declaration_list" => body,
+            _ => return self.get_node_text(node),
+        };
+
+        // Attributes, modifiers, type parameters, record parameters and bases
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..body.start_byte()]);
+        let mut type_def = format!("{} {{\n", header);
+
+        for child in body.named_children(&mut body.walk()) {
+            let member = match child.kind() {
+                "field_declaration" | "event_field_declaration" | "event_declaration"
+                | "enum_declaration" | "delegate_declaration" => self.get_node_text(child),
+                "property_declaration" | "indexer_declaration" => self.process_property(child),
+                "method_declaration" | "constructor_declaration" | "destructor_declaration"
+                | "operator_declaration" | "conversion_operator_declaration" => self.process_method(child),
+                "class_declaration" | "struct_declaration" | "record_declaration"
+                | "record_struct_declaration" | "interface_declaration" => {
+                    let mut child_cursor = child.walk();
+                    self.process_type(&mut child_cursor)
+                },
+                _ => continue,
+            };
+            type_def.push_str(&member.trim_end().lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+
+        type_def.push('}');
+        type_def
+    }
+
+    fn process_property(&self, node: Node) -> String {
+        let accessors = node.child_by_field_name("accessors");
+        let header_end = accessors
+            .or_else(|| node.child_by_field_name("value"))
+            .map(|n| n.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..header_end]);
+
+        let accessor_shapes = match accessors {
+            Some(accessor_list) => accessor_list.named_children(&mut accessor_list.walk())
+                .filter(|accessor| accessor.kind() == "accessor_declaration")
+                .map(|accessor| {
+                    let end = accessor.child_by_field_name("body")
+                        .map(|body| body.start_byte())
+                        .unwrap_or_else(|| accessor.end_byte());
+                    format!("{};", self.source_code[accessor.start_byte()..end].trim_end().trim_end_matches(';'))
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+            // Expression-bodied properties are read-only
+            None => "get;".to_string(),
+        };
+
+        format!("{} {{ {} }}", header, accessor_shapes)
+    }
+
+    fn process_method(&self, node: Node) -> String {
+        format!("{};", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Constructor initialisers (`: base(...)`) are part of the body for our purposes
+        let end = node.children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "constructor_initializer" | "block" | "arrow_expression_clause"))
+            .map(|child| child.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.normalize_lines(&self.source_code[node.start_byte()..end]).trim_end_matches(';').to_string()
+    }
+
+    fn normalize_lines(&self, text: &str) -> String {
+        // Attributes on their own lines keep the member's original indentation otherwise
+        text.trim().lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "namespace_declaration" | "file_scoped_namespace_declaration" => "namespace",
+            "class_declaration" => "class",
+            "struct_declaration" => "struct",
+            "record_declaration" | "record_struct_declaration" => "record",
+            "interface_declaration" => "interface",
+            "enum_declaration" => "enum",
+            "method_declaration" => "method",
+            "constructor_declaration" => "constructor",
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+    }
+}
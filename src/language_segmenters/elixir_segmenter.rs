@@ -0,0 +1,323 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_elixir::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, first_sentence, qualify_name};
+
+pub struct ElixirSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for ElixirSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_module(&mut cursor, "")
+    }
+}
+
+impl ElixirSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Elixir grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Elixir code");
+
+        Box::new(ElixirSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "source" => self.process_children(cursor),
+            "call" => self.process_call(cursor),
+            "unary_operator" => self.process_attribute(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        let mut last_function: Option<(String, String, usize)> = None;
+        if cursor.goto_first_child() {
+            loop {
+                // Multi-clause functions are collapsed into their first head
+                let function = self.get_function_key(cursor.node());
+                if function.is_none() || function != last_function {
+                    let node_text = self.process_node(cursor);
+                    if !node_text.trim().is_empty() {
+                        result.push_str(&node_text);
+                        result.push('\n');
+                    }
+                }
+                last_function = function;
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_call(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let target = node.child_by_field_name("target")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        match target.as_str() {
+            "defmodule" | "defprotocol" | "defimpl" => self.process_module(node, &target),
+            "def" | "defp" | "defmacro" | "defmacrop" | "defguard" | "defguardp" | "defdelegate" => {
+                self.get_function_head(node).unwrap_or_default()
+            },
+            _ => self.process_dsl_call(node),
+        }
+    }
+
+    fn process_module(&self, node: Node, keyword: &str) -> String {
+        let arguments = self.get_arguments(node)
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        let mut module_def = format!("{} {} do\n", keyword, arguments);
+        if let Some(do_block) = self.get_do_block(node) {
+            let mut block_cursor = do_block.walk();
+            let body_text = self.process_children(&mut block_cursor);
+            module_def.push_str(&body_text.lines().map(|line| format!("  {}\n", line)).collect::<String>());
+        }
+        module_def.push_str("end");
+        module_def
+    }
+
+    fn process_dsl_call(&self, node: Node) -> String {
+        // `use`, `import`, `alias`, `defstruct` and macros such as `schema` or `plug`
+        let do_block = match self.get_do_block(node) {
+            Some(do_block) => do_block,
+            None => return self.get_node_text(node),
+        };
+
+        let header = self.source_code[node.start_byte()..do_block.start_byte()].trim_end();
+        let mut block_cursor = do_block.walk();
+        let body_text = self.process_children(&mut block_cursor);
+        if body_text.is_empty() {
+            format!("{} do ... end", header)
+        } else {
+            let body = body_text.lines().map(|line| format!("  {}\n", line)).collect::<String>();
+            format!("{} do\n{}end", header, body)
+        }
+    }
+
+    fn process_attribute(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let operand = match node.child_by_field_name("operand") {
+            Some(operand) if operand.kind() == "call" => operand,
+            _ => return String::new(),
+        };
+        let attribute = operand.child_by_field_name("target")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        match attribute.as_str() {
+            "moduledoc" => {
+                // Only record whether the module is documented
+                let is_false = self.get_node_text(operand).trim_end().ends_with("false");
+                if is_false {
+                    "@moduledoc false".to_string()
+                } else {
+                    "@moduledoc \"...\"".to_string()
+                }
+            },
+            "spec" | "type" | "typep" | "opaque" | "callback" | "macrocallback" | "behaviour" => self.get_node_text(node),
+            _ => String::new(),
+        }
+    }
+
+    fn get_function_head(&self, node: Node) -> Option<String> {
+        let keyword = node.child_by_field_name("target").map(|n| self.get_node_text(n))?;
+        let key = match self.get_function_key(node) {
+            Some(key) => key,
+            // `defdelegate` has a single head
+            None => return Some(format!("{} {}", keyword, self.get_node_text(self.get_clause_head(node)?))),
+        };
+
+        // A clause that only takes variables names the arguments, otherwise a single
+        // clause's patterns would stand in for the whole function
+        let clauses = std::iter::successors(Some(node), |clause| clause.next_named_sibling())
+            .take_while(|clause| self.get_function_key(*clause).as_ref() == Some(&key));
+        for clause in clauses {
+            if let Some(head) = self.get_clause_head(clause).filter(|head| self.has_only_variables(*head)) {
+                return Some(format!("{} {}", keyword, self.get_node_text(head)));
+            }
+        }
+        let (_, name, arity) = key;
+        Some(format!("{} {}/{}", keyword, name, arity))
+    }
+
+    fn get_clause_head<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let head = self.get_arguments(node)?.named_child(0)?;
+
+        // Guards are clause specific, so they are dropped from the collapsed head
+        match head.kind() {
+            "binary_operator" if head.child_by_field_name("operator").map(|op| self.get_node_text(op)).as_deref() == Some("when") => {
+                head.child_by_field_name("left")
+            },
+            _ => Some(head),
+        }
+    }
+
+    fn has_only_variables(&self, head: Node) -> bool {
+        let arguments = match head.kind() {
+            "call" => self.get_arguments(head),
+            _ => return head.kind() == "identifier",
+        };
+        arguments.iter()
+            .flat_map(|arguments| arguments.named_children(&mut arguments.walk()).collect::<Vec<Node>>())
+            .all(|argument| match argument.kind() {
+                "identifier" => true,
+                // Default arguments, `name \\ default`
+                "binary_operator" => {
+                    argument.child_by_field_name("operator").map(|op| self.get_node_text(op)).as_deref() == Some("\\\\")
+                        && argument.child_by_field_name("left").is_some_and(|left| left.kind() == "identifier")
+                },
+                _ => false,
+            })
+    }
+
+    fn get_function_key(&self, node: Node) -> Option<(String, String, usize)> {
+        if node.kind() != "call" {
+            return None;
+        }
+        let keyword = node.child_by_field_name("target").map(|n| self.get_node_text(n))?;
+        if !matches!(keyword.as_str(), "def" | "defp" | "defmacro" | "defmacrop" | "defguard" | "defguardp") {
+            return None;
+        }
+
+        let mut head = self.get_arguments(node)?.named_child(0)?;
+        if head.kind() == "binary_operator" {
+            head = head.child_by_field_name("left")?;
+        }
+        match head.kind() {
+            "call" => {
+                let name = head.child_by_field_name("target").map(|n| self.get_node_text(n))?;
+                let arity = self.get_arguments(head).map(|args| args.named_child_count()).unwrap_or(0);
+                Some((keyword, name, arity))
+            },
+            _ => Some((keyword, self.get_node_text(head), 0)),
+        }
+    }
+
+    fn get_arguments<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        node.named_children(&mut node.walk()).find(|child| child.kind() == "arguments")
+    }
+
+    fn get_do_block<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        node.named_children(&mut node.walk()).find(|child| child.kind() == "do_block")
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_module(&self, cursor: &mut TreeCursor, scope: &str) -> Vec<Symbol> {
+        let mut symbols: Vec<Symbol> = Vec::new();
+
+        loop {
+            let node = cursor.node();
+            let target = node.child_by_field_name("target")
+                .map(|n| self.get_node_text(n))
+                .unwrap_or_default();
+
+            match (node.kind(), target.as_str()) {
+                ("call", "def" | "defp" | "defmacro" | "defmacrop" | "defguard" | "defguardp") => {
+                    if let Some((keyword, name, arity)) = self.get_function_key(node) {
+                        let kind = match keyword.as_str() {
+                            "defmacro" | "defmacrop" => "macro",
+                            "defguard" | "defguardp" => "guard",
+                            _ => "function",
+                        };
+                        let name = format!("{}/{}", name, arity);
+                        // Every clause is its own call, they are merged into one symbol
+                        match symbols.last_mut() {
+                            Some(last) if last.kind == kind && last.name == name => {
+                                last.byte_range.end = node.end_byte();
+                                last.end_line = node.end_position().row + 1;
+                            },
+                            _ => {
+                                let mut symbol = Symbol::from_node(kind, &name, &qualify_name(scope, &name, "."), node, &self.source_code);
+                                symbol.signature = self.get_function_head(node).unwrap_or(symbol.signature);
+                                symbol.visibility = Some(if keyword.ends_with('p') { "private" } else { "public" }.to_string());
+                                symbol.doc = self.get_doc(node).or(symbol.doc);
+                                symbols.push(symbol);
+                            },
+                        }
+                    }
+                },
+                ("call", "defmodule" | "defprotocol" | "defimpl") => {
+                    let kind = match target.as_str() {
+                        "defprotocol" => "protocol",
+                        "defimpl" => "impl",
+                        _ => "module",
+                    };
+                    let module_name = self.get_arguments(node)
+                        .and_then(|arguments| arguments.named_child(0))
+                        .map(|n| self.get_node_text(n))
+                        .unwrap_or_default();
+                    let qualified_name = qualify_name(scope, &module_name, ".");
+                    let mut symbol = Symbol::from_node(kind, &module_name, &qualified_name, node, &self.source_code);
+                    let moduledoc = self.get_do_block(node).and_then(|block| {
+                        block.named_children(&mut block.walk()).find_map(|child| self.get_attribute_text(child, "moduledoc"))
+                    });
+                    symbol.doc = moduledoc.or(symbol.doc);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_module(cursor, &qualified_name);
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                _ => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_module(cursor, scope));
+                        cursor.goto_parent();
+                    }
+                }
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_doc(&self, function: Node) -> Option<String> {
+        // `@doc` sits among the other attributes in front of the first clause
+        let mut previous = function.prev_named_sibling();
+        while let Some(attribute) = previous.filter(|n| n.kind() == "unary_operator") {
+            if let Some(doc) = self.get_attribute_text(attribute, "doc") {
+                return Some(doc);
+            }
+            previous = attribute.prev_named_sibling();
+        }
+        None
+    }
+
+    fn get_attribute_text(&self, attribute: Node, name: &str) -> Option<String> {
+        let call = attribute.child_by_field_name("operand").filter(|operand| operand.kind() == "call")?;
+        if call.child_by_field_name("target").map(|n| self.get_node_text(n)).as_deref() != Some(name) {
+            return None;
+        }
+        let content = self.get_arguments(call)?
+            .named_child(0)
+            .filter(|string| string.kind() == "string")?
+            .named_children(&mut call.walk())
+            .find(|child| child.kind() == "quoted_content")?;
+        first_sentence(&self.get_node_text(content).split_whitespace().collect::<Vec<&str>>().join(" "))
+    }
+}
@@ -0,0 +1,185 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_erlang::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, qualify_name};
+
+pub struct ErlangSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for ErlangSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func(&mut cursor)
+    }
+}
+
+impl ErlangSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Erlang grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Erlang code");
+
+        Box::new(ErlangSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "source_file" => self.process_source_file(cursor),
+            "module_attribute" | "behaviour_attribute" | "export_attribute" | "import_attribute"
+            | "export_type_attribute" | "record_decl" | "type_alias" | "opaque" | "spec" | "callback"
+            | "pp_include" | "pp_include_lib" | "pp_define" => self.get_node_text(node),
+            "fun_decl" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_source_file(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        let mut last_function = None;
+        if cursor.goto_first_child() {
+            loop {
+                // Each clause can be its own `fun_decl`, only keep the first head of a function
+                let function = self.get_function_key(cursor.node());
+                if function.is_none() || function != last_function {
+                    let node_text = self.process_node(cursor);
+                    if !node_text.trim().is_empty() {
+                        result.push_str(&node_text);
+                        result.push('\n');
+                    }
+                }
+                last_function = function;
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let key = match self.get_function_key(node) {
+            Some(key) => key,
+            None => return String::new(),
+        };
+
+        // `fact(N) -> ...` if some clause binds plain variables, `fact/1 -> ...` if every one matches on patterns
+        let head = std::iter::successors(Some(node), |clause| clause.next_named_sibling())
+            .take_while(|clause| self.get_function_key(*clause).as_ref() == Some(&key))
+            .filter_map(|function| function.child_by_field_name("clause"))
+            .find(|clause| self.has_only_variables(*clause))
+            .map(|clause| self.get_head(clause))
+            .unwrap_or_else(|| format!("{}/{}", key.0, key.1));
+        format!("{} -> ...", head)
+    }
+
+    fn get_head(&self, clause: Node) -> String {
+        let function_name = clause.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+        let args = clause.child_by_field_name("args")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "()".to_string());
+        format!("{}{}", function_name, args)
+    }
+
+    fn has_only_variables(&self, clause: Node) -> bool {
+        clause.child_by_field_name("args")
+            .is_none_or(|args| args.named_children(&mut args.walk()).all(|arg| arg.kind() == "var"))
+    }
+
+    fn get_function_key(&self, node: Node) -> Option<(String, usize)> {
+        if node.kind() != "fun_decl" {
+            return None;
+        }
+        let clause = node.child_by_field_name("clause")?;
+        let function_name = clause.child_by_field_name("name").map(|n| self.get_node_text(n))?;
+        let arity = clause.child_by_field_name("args")
+            .map(|args| args.named_child_count())
+            .unwrap_or(0);
+        Some((function_name, arity))
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func(&self, cursor: &mut TreeCursor) -> Vec<Symbol> {
+        let root = cursor.node();
+        let mut symbols: Vec<Symbol> = Vec::new();
+        let mut module: Option<Symbol> = None;
+        let exports = self.get_exports(root);
+
+        if cursor.goto_first_child() {
+            loop {
+                let node = cursor.node();
+                let name = node.child_by_field_name("name").map(|n| self.get_node_text(n));
+                let scope = module.as_ref().map(|module| module.name.clone()).unwrap_or_default();
+                match (node.kind(), name) {
+                    ("module_attribute", Some(name)) => {
+                        // The attribute is a single line, but the module is the whole file
+                        let mut symbol = Symbol::from_node("module", &name, &name, root, &self.source_code);
+                        symbol.signature = self.get_node_text(node);
+                        module = Some(symbol);
+                    },
+                    ("record_decl", Some(name)) => {
+                        symbols.push(Symbol::from_node("record", &name, &qualify_name(&scope, &name, ":"), node, &self.source_code));
+                    },
+                    _ => {
+                        if let Some((function_name, arity)) = self.get_function_key(node) {
+                            let name = format!("{}/{}", function_name, arity);
+                            // Each clause can be its own `fun_decl`, they all belong to one function
+                            match symbols.last_mut() {
+                                Some(last) if last.kind == "function" && last.name == name => {
+                                    last.byte_range.end = node.end_byte();
+                                    last.end_line = node.end_position().row + 1;
+                                },
+                                _ => {
+                                    let mut symbol = Symbol::from_node("function", &name, &qualify_name(&scope, &name, ":"), node, &self.source_code);
+                                    symbol.signature = name.clone();
+                                    symbol.visibility = Some(if exports.contains(&name) { "public" } else { "private" }.to_string());
+                                    symbols.push(symbol);
+                                },
+                            }
+                        }
+                    },
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+
+        match module {
+            Some(mut module) => {
+                module.children = symbols;
+                vec![module]
+            },
+            None => symbols,
+        }
+    }
+
+    fn get_exports(&self, root: Node) -> Vec<String> {
+        root.named_children(&mut root.walk())
+            .filter(|form| form.kind() == "export_attribute")
+            .flat_map(|export| {
+                let text: String = self.get_node_text(export).split_whitespace().collect();
+                let start = text.find('[').map(|i| i + 1).unwrap_or(0);
+                let end = text.rfind(']').unwrap_or(text.len());
+                text[start..end].split(',').map(|function| function.to_string()).collect::<Vec<String>>()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,166 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_go::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct GoSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for GoSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_type(&mut cursor, "", false)
+    }
+}
+
+impl GoSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Go grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Go code");
+
+        Box::new(GoSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "source_file" => self.process_source_file(cursor),
+            "package_clause" => self.get_node_text(node),
+            "import_declaration" => self.get_node_text(node),
+            "type_declaration" => self.get_node_text(node),
+            "const_declaration" => self.get_node_text(node),
+            "var_declaration" => self.process_var(cursor),
+            "function_declaration" | "method_declaration" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_source_file(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_var(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+
+        // Keep the declared names and types, drop the initialisers
+        node.named_children(&mut node.walk())
+            .filter(|spec| spec.kind() == "var_spec")
+            .map(|spec| {
+                let names: Vec<String> = spec.children_by_field_name("name", &mut spec.walk())
+                    .filter(|n| n.kind() == "identifier")
+                    .map(|n| self.get_node_text(n))
+                    .collect();
+                match spec.child_by_field_name("type") {
+                    Some(var_type) => format!("var {} {}", names.join(", "), self.get_node_text(var_type)),
+                    None => format!("var {}", names.join(", ")),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{}\n", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Everything up to the body keeps the receiver, type parameters and results
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].trim_end().to_string()
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_type(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    // Capitalised names are exported
+                    let is_exported = name.rsplit('.').next().and_then(|n| n.chars().next()).is_some_and(|c| c.is_uppercase());
+                    symbol.visibility = Some(if is_exported { "public" } else { "private" }.to_string());
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_type(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_type(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let name = self.get_node_text(node.child_by_field_name("name")?);
+        match node.kind() {
+            "function_declaration" => Some(("function", name)),
+            "method_declaration" => {
+                // Methods live at the top level, so qualify them with their receiver type
+                let receiver_type = node.child_by_field_name("receiver")
+                    .and_then(|receiver| receiver.named_child(0))
+                    .and_then(|parameter| parameter.child_by_field_name("type"))
+                    .map(|receiver_type| self.get_node_text(receiver_type))
+                    .unwrap_or_default();
+                let receiver_type = receiver_type.trim_start_matches('*');
+                let receiver_type = receiver_type.split('[').next().unwrap_or(receiver_type);
+                Some(("method", qualify_name(receiver_type, &name, ".")))
+            },
+            "type_spec" => {
+                let kind = match node.child_by_field_name("type").map(|n| n.kind()) {
+                    Some("struct_type") => "struct",
+                    Some("interface_type") => "interface",
+                    _ => "type",
+                };
+                Some((kind, name))
+            },
+            "type_alias" => Some(("type", name)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::code_segmenter::CodeSegmenter;
+use super::{CppSegmenter, ObjcSegmenter};
+
+/// `.h` files are shared between C, C++ and Objective-C, so pick the grammar from the contents.
+pub struct HeaderSegmenter;
+
+impl HeaderSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let is_objc = code.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("@interface")
+                || line.starts_with("@protocol")
+                || line.starts_with("@class")
+                || line.starts_with("#import")
+        });
+
+        if is_objc {
+            ObjcSegmenter::new(code)
+        } else {
+            CppSegmenter::new(code)
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_html::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, qualify_name};
+
+pub struct HtmlSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for HtmlSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor).trim_end().to_string()
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_ids(&mut cursor, "")
+    }
+}
+
+impl HtmlSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading HTML grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse HTML");
+
+        Box::new(HtmlSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "fragment" => self.process_children(cursor),
+            "doctype" => self.get_node_text(node),
+            "element" | "script_element" | "style_element" => self.process_element(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        let mut last_text = String::new();
+        let mut repeats = 0;
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                // Collapse runs of identical siblings such as list items or table rows
+                if !node_text.trim().is_empty() && node_text == last_text {
+                    repeats += 1;
+                } else if !node_text.trim().is_empty() {
+                    result.push_str(&self.format_repeats(repeats));
+                    result.push_str(&node_text);
+                    result.push('\n');
+                    last_text = node_text;
+                    repeats = 0;
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            result.push_str(&self.format_repeats(repeats));
+            cursor.goto_parent();
+        }
+        result
+    }
+
+    fn format_repeats(&self, repeats: usize) -> String {
+        if repeats > 0 {
+            format!("... ({} more)\n", repeats)
+        } else {
+            String::new()
+        }
+    }
+
+    fn process_element(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let start_tag = match node.named_child(0) {
+            Some(tag) if tag.kind() == "start_tag" || tag.kind() == "self_closing_tag" => tag,
+            _ => return String::new(),
+        };
+
+        let mut result = self.get_tag_header(start_tag);
+        let children_text = self.process_children(cursor)
+            .lines()
+            .map(|line| format!("  {}\n", line))
+            .collect::<String>();
+        if !children_text.is_empty() {
+            result.push('\n');
+            result.push_str(children_text.trim_end());
+        }
+        result
+    }
+
+    fn get_tag_header(&self, start_tag: Node) -> String {
+        let tag_name = start_tag.named_children(&mut start_tag.walk())
+            .find(|child| child.kind() == "tag_name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        let kept_attributes: &[&str] = match tag_name.to_ascii_lowercase().as_str() {
+            "script" | "img" | "iframe" => &["id", "class", "src"],
+            "link" | "a" => &["id", "class", "rel", "href"],
+            _ => &["id", "class"],
+        };
+
+        let attributes: String = start_tag.named_children(&mut start_tag.walk())
+            .filter(|child| child.kind() == "attribute")
+            .filter_map(|attribute| {
+                let attribute_name = attribute.named_child(0).map(|n| self.get_node_text(n))?;
+                if !kept_attributes.contains(&attribute_name.to_ascii_lowercase().as_str()) {
+                    return None;
+                }
+                Some(match attribute.named_child(1) {
+                    Some(value) => format!(" {}={}", attribute_name, self.get_node_text(value)),
+                    None => format!(" {}", attribute_name),
+                })
+            })
+            .collect();
+
+        format!("<{}{}>", tag_name, attributes)
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_ids(&self, cursor: &mut TreeCursor, scope: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let qualified_name = qualify_name(scope, &name, " > ");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if let Some(start_tag) = node.named_child(0) {
+                        symbol.signature = self.get_tag_header(start_tag);
+                    }
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_ids(cursor, &qualified_name);
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_ids(cursor, scope));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        if !matches!(node.kind(), "element" | "script_element" | "style_element") {
+            return None;
+        }
+        let start_tag = node.named_child(0).filter(|tag| tag.kind() == "start_tag" || tag.kind() == "self_closing_tag")?;
+        let tag_name = start_tag.named_children(&mut start_tag.walk())
+            .find(|child| child.kind() == "tag_name")
+            .map(|n| self.get_node_text(n))?;
+        Some(("element", format!("{}#{}", tag_name, self.get_id(start_tag)?)))
+    }
+
+    fn get_id(&self, start_tag: Node) -> Option<String> {
+        start_tag.named_children(&mut start_tag.walk())
+            .filter(|child| child.kind() == "attribute")
+            .find(|attribute| attribute.named_child(0).is_some_and(|n| self.get_node_text(n).eq_ignore_ascii_case("id")))
+            .and_then(|attribute| attribute.named_child(1))
+            .map(|value| self.get_node_text(value).trim_matches(|c| c == '"' || c == '\'').to_string())
+    }
+}
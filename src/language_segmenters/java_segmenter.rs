@@ -1,7 +1,6 @@
 use tree_sitter::{Parser, Node, TreeCursor};
-use std::fmt::Write;
 use tree_sitter_java::language;
-use crate::code_segmenter::CodeSegmenter;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
 
 pub struct JavaSegmenter {
     tree: tree_sitter::Tree,
@@ -14,9 +13,9 @@ impl CodeSegmenter for JavaSegmenter {
         self.process_node(&mut cursor)
     }
 
-    fn extract_functions_classes(&self) -> String {
+    fn extract_symbols(&self) -> Vec<Symbol> {
         let mut cursor = self.tree.walk();
-        self.process_node_func_class(&mut cursor)
+        self.process_node_func_class(&mut cursor, "", false)
     }
 }
 
@@ -40,10 +39,13 @@ impl JavaSegmenter {
             "program" => self.process_program(cursor),
             "package_declaration" => self.get_node_text(node),
             "import_declaration" => self.get_node_text(node),
-            "class_declaration" => self.process_class(cursor),
+            "class_declaration" | "interface_declaration" | "enum_declaration"
+            | "record_declaration" | "annotation_type_declaration" => self.process_class(cursor),
             "method_declaration" => self.process_method(cursor),
-            "constructor_declaration" => self.process_constructor(cursor),
-            "field_declaration" => self.get_node_text(node),
+            "constructor_declaration" | "compact_constructor_declaration" => self.process_constructor(cursor),
+            "field_declaration" | "constant_declaration" | "annotation_type_element_declaration" => {
+                self.normalize_lines(&self.get_node_text(node))
+            },
             _ => String::new(),
         }
     }
@@ -55,7 +57,7 @@ impl JavaSegmenter {
                 let node_text = self.process_node(cursor);
                 if !node_text.trim().is_empty() {
                     result.push_str(&node_text);
-                    result.push_str("\n");
+                    result.push('\n');
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -68,107 +70,100 @@ impl JavaSegmenter {
 
     fn process_class(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let class_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnnamedClass".to_string());
-        
-        let mut class_def = format!("class {} {{\n", class_name);
-        
-        if let Some(body) = node.child_by_field_name("body") {
-            for child in body.named_children(&mut body.walk()) {
-                match child.kind() {
-                    "method_declaration" => {
-                        let mut child_cursor = child.walk();
-                        let method_def = self.process_node(&mut child_cursor);
-                        class_def.push_str(&method_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
-                    },
-                    "constructor_declaration" => {
-                        let mut child_cursor = child.walk();
-                        let constructor_def = self.process_constructor(&mut child_cursor);
-                        class_def.push_str(&constructor_def);
-                    },
-                    "field_declaration" => {
-                        class_def.push_str(&self.get_node_text(child));
-                        class_def.push('\n');
-                    },
-                    _ => {}
-                }
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.normalize_lines(&self.get_node_text(node)),
+        };
+
+        // Annotations, modifiers, type parameters, record components, extends and implements
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..body.start_byte()]);
+        let mut class_def = format!("{} {{\n", header);
+
+        let enum_constants: Vec<String> = body.named_children(&mut body.walk())
+            .filter(|child| child.kind() == "enum_constant")
+            .map(|constant| {
+                // Constant-specific class bodies are implementation details
+                let end = constant.child_by_field_name("body")
+                    .map(|body| body.start_byte())
+                    .unwrap_or_else(|| constant.end_byte());
+                self.normalize_lines(&self.source_code[constant.start_byte()..end])
+            })
+            .collect();
+        if !enum_constants.is_empty() {
+            class_def.push_str(&format!("    {};\n", enum_constants.join(", ")));
+        }
+
+        // Enum members after the constants sit in their own node
+        let members: Vec<Node> = body.named_children(&mut body.walk())
+            .flat_map(|child| match child.kind() {
+                "enum_body_declarations" => child.named_children(&mut child.walk()).collect(),
+                _ => vec![child],
+            })
+            .collect();
+
+        for member in members {
+            if matches!(member.kind(), "enum_constant" | "line_comment" | "block_comment") {
+                continue;
             }
+            let mut member_cursor = member.walk();
+            let member_def = self.process_node(&mut member_cursor);
+            class_def.push_str(&member_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
         }
         
-        class_def.push_str("}\n");
+        class_def.push('}');
         class_def
     }
 
     fn process_method(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let method_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "unnamedMethod".to_string());
-        let return_type = node.child_by_field_name("type")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "void".to_string());
-        let parameters = node.child_by_field_name("parameters")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "()".to_string());
-        
-        format!("{} {}{};\n", return_type, method_name, parameters)
+        // Annotations, modifiers, type parameters, return type, parameters and throws
+        format!("{};", self.get_header(node))
     }
 
     fn process_constructor(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let constructor_text = self.get_node_text(node);
-        format!("    // Constructor implementation\n {} ", constructor_text)
+        format!("{};", self.get_header(node))
+    }
+
+    fn get_header(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.normalize_lines(self.source_code[node.start_byte()..end].trim_end().trim_end_matches(';'))
+    }
+
+    fn normalize_lines(&self, text: &str) -> String {
+        // Annotations on their own lines keep the member's original indentation otherwise
+        text.trim().lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
     }
 
     fn get_node_text(&self, node: Node) -> String {
         self.source_code[node.start_byte()..node.end_byte()].to_string()
     }
 
-    #[allow(dead_code)]
-    fn process_node_func_class(&self, cursor: &mut TreeCursor) -> String {
-        let mut result = String::new();
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
 
         loop {
             let node = cursor.node();
 
-            match node.kind() {
-                "method_declaration" | "constructor_declaration" => {
-                    // let name = self.get_name(node);
-                    let start_line = node.start_position().row;
-                    let end_line = node.end_position().row;
-
-                    writeln!(&mut result, "// Code for: {}", self.get_line(start_line)).unwrap();
-                    writeln!(&mut result, "{} {{ ... }}", self.get_signature(node)).unwrap();
-                    writeln!(&mut result, "// End: {}", self.get_line(end_line)).unwrap();
-                    writeln!(&mut result).unwrap();
-
-                    // Skip the children (implementation details)
-                    cursor.goto_first_child();
-                    while cursor.goto_next_sibling() {}
-                    cursor.goto_parent();
-                },
-                "class_declaration" => {
-                    let class_text = self.get_node_text(node.child(0).unwrap_or(node));
-                    writeln!(&mut result, "{} {{", class_text).unwrap();
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
                         cursor.goto_parent();
                     }
-                    writeln!(&mut result, "}}").unwrap();
+                    symbols.push(symbol);
                 },
-                "program" | "class_body" => {
+                None => {
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
                         cursor.goto_parent();
                     }
                 },
-                _ => {
-                    if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
-                        cursor.goto_parent();
-                    }
-                }
             }
 
             if !cursor.goto_next_sibling() {
@@ -176,20 +171,19 @@ impl JavaSegmenter {
             }
         }
 
-        result
-    }
-
-    // fn get_name(&self, node: Node) -> &str {
-    //     node.child_by_field_name("name")
-    //         .and_then(|name_node| name_node.utf8_text(self.source_code.as_bytes()).ok())
-    //         .unwrap_or("unknown")
-    // }
-
-    fn get_line(&self, line_number: usize) -> &str {
-        self.source_code.lines().nth(line_number).unwrap_or("")
+        symbols
     }
 
-    fn get_signature(&self, node: Node) -> String {
-        self.get_node_text(node.named_child(0).unwrap_or(node))
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "class_declaration" => "class",
+            "interface_declaration" | "annotation_type_declaration" => "interface",
+            "enum_declaration" => "enum",
+            "record_declaration" => "record",
+            "method_declaration" => "method",
+            "constructor_declaration" | "compact_constructor_declaration" => "constructor",
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
     }
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
 
 use tree_sitter::{Parser, Node, TreeCursor};
+use std::fmt::Write;
 use tree_sitter_javascript::language;
-use crate::code_segmenter::CodeSegmenter;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
 
 pub struct JavaScriptSegmenter {
     tree: tree_sitter::Tree,
@@ -16,8 +17,9 @@ impl CodeSegmenter for JavaScriptSegmenter {
         // String::new()
     }
 
-    fn extract_functions_classes(&self) -> String {
-        String::new() // Placeholder for potential future implementation
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
     }
 }
 
@@ -41,6 +43,7 @@ impl JavaScriptSegmenter {
             "function_declaration" => self.process_function(cursor),
             "method_definition" => self.process_method(cursor),
             "export_statement" => self.process_export(cursor),
+            "lexical_declaration" | "variable_declaration" => self.process_lexical_declaration(cursor),
             "comment" => String::new(), // Ignore comments
             _ => self.get_node_text(node),
         }
@@ -53,7 +56,7 @@ impl JavaScriptSegmenter {
                 let node_text = self.process_node(cursor);
                 if !node_text.trim().is_empty() {
                     result.push_str(&node_text);
-                    result.push_str("\n");
+                    result.push('\n');
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -66,25 +69,153 @@ impl JavaScriptSegmenter {
 
     fn process_export(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        if let Some(declaration) = node.child(1) {
-            match declaration.kind() {
-                "function_declaration" | "class_declaration" => {
-                    let mut declaration_cursor = declaration.walk();
-                    format!("export {}", self.process_node(&mut declaration_cursor))
+        let declaration = match node.child_by_field_name("declaration") {
+            Some(declaration) => declaration,
+            // `export default App;`, `export { a, b }` and `export * from "./b"`
+            None => return self.get_node_text(node),
+        };
+        // `export ` or `export default `
+        let prefix = &self.source_code[node.start_byte()..declaration.start_byte()];
+        let mut declaration_cursor = declaration.walk();
+        format!("{}{}", prefix, self.process_node(&mut declaration_cursor).trim_end())
+    }
+
+    fn process_lexical_declaration(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let component = node.named_children(&mut node.walk())
+            .find(|child| child.kind() == "variable_declarator")
+            .and_then(|declarator| self.get_declared_function(declarator))
+            .filter(|function| self.is_component(node, *function));
+
+        match component {
+            Some(function) => self.process_component(node, function),
+            None => self.process_variable_declaration(node),
+        }
+    }
+
+    fn process_variable_declaration(&self, node: Node) -> String {
+        // `const`, `let` or `var`
+        let kind = node.child(0)
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "const".to_string());
+
+        let mut result = String::new();
+        for declarator in node.named_children(&mut node.walk()) {
+            if declarator.kind() != "variable_declarator" {
+                continue;
+            }
+            let name = match declarator.child_by_field_name("name") {
+                Some(name) => self.get_node_text(name),
+                None => continue,
+            };
+
+            let value = match declarator.child_by_field_name("value") {
+                // Arrow functions and function expressions become signatures
+                Some(value) if matches!(value.kind(), "arrow_function" | "function" | "function_expression") => {
+                    format!(" = {} {{ }}", self.get_signature(value))
                 },
-                "default_keyword" => {
-                    if let Some(class_decl) = node.child(2) {
-                        let mut class_cursor = class_decl.walk();
-                        format!("export default {}", self.process_class(&mut class_cursor))
+                Some(value) => {
+                    let value_text = self.get_node_text(value);
+                    if value_text.contains('\n') {
+                        " = ...".to_string()
                     } else {
-                        String::new()
+                        format!(" = {}", value_text)
                     }
                 },
-                _ => String::new(),
+                None => String::new(),
+            };
+            writeln!(&mut result, "{} {}{};", kind, name, value).unwrap();
+        }
+
+        result.trim_end().to_string()
+    }
+
+    fn get_declared_function<'a>(&self, declarator: Node<'a>) -> Option<Node<'a>> {
+        let value = declarator.child_by_field_name("value")?;
+        match value.kind() {
+            "arrow_function" | "function" | "function_expression" => Some(value),
+            // `React.memo(...)` and `forwardRef(...)` wrap the component itself
+            "call_expression" => value.child_by_field_name("arguments")?
+                .named_children(&mut value.walk())
+                .find(|argument| matches!(argument.kind(), "arrow_function" | "function" | "function_expression")),
+            _ => None,
+        }
+    }
+
+    fn is_component(&self, declaration: Node, function: Node) -> bool {
+        // Components are capitalised by convention and render JSX
+        let name = match declaration.kind() {
+            "lexical_declaration" | "variable_declaration" => {
+                declaration.named_child(0).and_then(|declarator| declarator.child_by_field_name("name"))
+            },
+            _ => declaration.child_by_field_name("name"),
+        };
+        let is_capitalised = name
+            .and_then(|n| self.get_node_text(n).chars().next())
+            .is_some_and(|c| c.is_ascii_uppercase());
+        is_capitalised && function.child_by_field_name("body").is_some_and(|body| self.contains_jsx(body))
+    }
+
+    fn contains_jsx(&self, node: Node) -> bool {
+        node.kind().starts_with("jsx_")
+            || node.named_children(&mut node.walk()).any(|child| self.contains_jsx(child))
+    }
+
+    fn process_component(&self, declaration: Node, function: Node) -> String {
+        let body = match function.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(declaration),
+        };
+
+        // Everything around the body, e.g. `const Card = ({ title }) =>` and `);`
+        let header = self.source_code[declaration.start_byte()..body.start_byte()].trim_end();
+        let trailer = &self.source_code[body.end_byte()..declaration.end_byte()];
+
+        let mut component_def = format!("{} {{\n", header);
+        let hooks = self.get_hooks(body);
+        if !hooks.is_empty() {
+            writeln!(&mut component_def, "    // hooks: {}", hooks.join(", ")).unwrap();
+        }
+        component_def.push_str("    return <... />;\n");
+        component_def.push('}');
+        component_def.push_str(trailer);
+        component_def
+    }
+
+    fn get_hooks(&self, node: Node) -> Vec<String> {
+        let mut hooks: Vec<String> = Vec::new();
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            if current.kind() == "call_expression" {
+                // `useState(...)` or `React.useState(...)`
+                let hook = current.child_by_field_name("function")
+                    .map(|function| function.child_by_field_name("property").unwrap_or(function))
+                    .map(|name| self.get_node_text(name))
+                    .filter(|name| self.is_hook_name(name));
+                if let Some(hook) = hook {
+                    if !hooks.contains(&hook) {
+                        hooks.push(hook);
+                    }
+                }
             }
-        } else {
-            String::new()
+            // Reversed so hooks come out in source order
+            let children: Vec<Node> = current.named_children(&mut current.walk()).collect();
+            stack.extend(children.into_iter().rev());
         }
+        hooks
+    }
+
+    fn is_hook_name(&self, name: &str) -> bool {
+        name == "use" || name.strip_prefix("use").and_then(|rest| rest.chars().next()).is_some_and(|c| c.is_ascii_uppercase())
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Parameters of arrow functions and function expressions
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let signature = self.source_code[node.start_byte()..end].trim();
+        signature.lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
     }
 
     fn process_class(&self, cursor: &mut TreeCursor) -> String {
@@ -118,6 +249,10 @@ impl JavaScriptSegmenter {
 
     fn process_function(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
+        if self.is_component(node, node) {
+            return self.process_component(node, node);
+        }
+
         let func_name = node.child_by_field_name("name")
             .map(|n| self.get_node_text(n))
             .unwrap_or_else(|| "unnamed".to_string());
@@ -154,31 +289,29 @@ impl JavaScriptSegmenter {
         self.source_code[node.start_byte()..node.end_byte()].to_string()
     }
 
-    #[allow(dead_code)]
-    fn process_node_func_class(&self, cursor: &mut TreeCursor) -> String {
-        let mut result = String::new();
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
 
         loop {
             let node = cursor.node();
 
-            match node.kind() {
-                "function_declaration" | "class_declaration" => {
-                    // let name = self.get_name(node);
-                    let node_text = self.process_node(cursor);
-                    result.push_str(&node_text);
-                },
-                "program" => {
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
                         cursor.goto_parent();
                     }
+                    symbols.push(symbol);
                 },
-                _ => {
+                None => {
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
                         cursor.goto_parent();
                     }
-                }
+                },
             }
 
             if !cursor.goto_next_sibling() {
@@ -186,12 +319,27 @@ impl JavaScriptSegmenter {
             }
         }
 
-        result
+        symbols
     }
 
-    // fn get_name(&self, node: Node) -> String {
-    //     node.child_by_field_name("name")
-    //         .map(|name_node| self.get_node_text(name_node))
-    //         .unwrap_or_else(|| "unnamed".to_string())
-    // }
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "function_declaration" | "generator_function_declaration" if self.is_component(node, node) => "component",
+            "function_declaration" | "generator_function_declaration" => "function",
+            "class_declaration" | "class" => "class",
+            "method_definition" => "method",
+            // `const handler = () => {}` and `const Model = class {}`
+            "variable_declarator" => match self.get_declared_function(node) {
+                Some(function) if self.is_component(node, function) => "component",
+                Some(_) => "function",
+                None => match node.child_by_field_name("value").map(|value| value.kind()) {
+                    Some("generator_function") => "function",
+                    Some("class") => "class",
+                    _ => return None,
+                },
+            },
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+    }
 }
\ No newline at end of file
@@ -0,0 +1,115 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_json::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol};
+
+pub struct JsonSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for JsonSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_keys(&mut cursor)
+    }
+}
+
+impl JsonSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading JSON grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse JSON");
+
+        Box::new(JsonSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "document" => node.named_children(&mut node.walk())
+                .filter(|child| child.kind() != "comment")
+                .map(|value| self.get_schema(value))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            _ => String::new(),
+        }
+    }
+
+    fn get_schema(&self, node: Node) -> String {
+        match node.kind() {
+            "object" => self.get_object_schema(node),
+            "array" => self.get_array_schema(node),
+            "string" => "string".to_string(),
+            "number" => "number".to_string(),
+            "true" | "false" => "boolean".to_string(),
+            "null" => "null".to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn get_object_schema(&self, node: Node) -> String {
+        let pairs: String = node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "pair")
+            .filter_map(|pair| {
+                let key = pair.child_by_field_name("key").map(|n| self.get_node_text(n))?;
+                let value = pair.child_by_field_name("value")
+                    .map(|n| self.get_schema(n))
+                    .unwrap_or_default();
+                Some(format!("{}: {},\n", key, value))
+            })
+            .collect();
+
+        if pairs.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}}}", pairs.lines().map(|line| format!("    {}\n", line)).collect::<String>())
+        }
+    }
+
+    fn get_array_schema(&self, node: Node) -> String {
+        // Arrays are usually homogeneous, the first element stands in for the rest
+        match node.named_children(&mut node.walk()).find(|child| child.kind() != "comment") {
+            Some(element) => format!("[{}]", self.get_schema(element)),
+            None => "[]".to_string(),
+        }
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_keys(&self, cursor: &mut TreeCursor) -> Vec<Symbol> {
+        let root = cursor.node();
+
+        let objects: Vec<Node> = root.named_children(&mut root.walk())
+            .filter(|value| value.kind() == "object")
+            .collect();
+        let top_level_pairs: Vec<Node> = objects.iter()
+            .flat_map(|object| object.named_children(&mut object.walk()).collect::<Vec<Node>>())
+            .filter(|child| child.kind() == "pair")
+            .collect();
+
+        top_level_pairs.into_iter()
+            .map(|pair| {
+                let key = pair.child_by_field_name("key")
+                    .map(|n| self.get_node_text(n))
+                    .unwrap_or_default();
+                let value = pair.child_by_field_name("value")
+                    .map(|n| self.get_schema(n))
+                    .unwrap_or_default();
+                let name = key.trim_matches('"');
+                let mut symbol = Symbol::from_node("key", name, name, pair, &self.source_code);
+                symbol.signature = format!("{}: {}", key, value.lines().next().unwrap_or(""));
+                symbol
+            })
+            .collect()
+    }
+}
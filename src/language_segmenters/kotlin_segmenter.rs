@@ -1,7 +1,7 @@
 use tree_sitter::{Parser, Node, TreeCursor};
 use std::fmt::Write;
 use tree_sitter_kotlin::language;
-use crate::code_segmenter::CodeSegmenter;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
 
 pub struct KotlinSegmenter {
     tree: tree_sitter::Tree,
@@ -14,16 +14,16 @@ impl CodeSegmenter for KotlinSegmenter {
         self.process_node(&mut cursor)
     }
 
-    fn extract_functions_classes(&self) -> String {
+    fn extract_symbols(&self) -> Vec<Symbol> {
         let mut cursor = self.tree.walk();
-        self.process_node_func_class(&mut cursor)
+        self.process_node_func_class(&mut cursor, "", false)
     }
 }
 
 impl KotlinSegmenter {
     pub fn new(code: String) -> Box<dyn CodeSegmenter> {
         let mut parser = Parser::new();
-        parser.set_language(tree_sitter_kotlin.language())
+        parser.set_language(language())
             .expect("Error loading Kotlin grammar");
         let tree = parser.parse(&code, None)
             .expect("Failed to parse Kotlin code");
@@ -37,13 +37,14 @@ impl KotlinSegmenter {
     fn process_node(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
         match node.kind() {
-            "file" => self.process_file(cursor),
-            "package_directive" => self.get_node_text(node),
-            "import_directive" => self.get_node_text(node),
-            "class" => self.process_class(cursor),
-            "function" => self.process_function(cursor),
-            "constructor" => self.process_constructor(cursor),
-            "property" => self.get_node_text(node),
+            "source_file" => self.process_file(cursor),
+            "file_annotation" | "package_header" | "type_alias" => self.get_node_text(node),
+            "import_list" => self.process_imports(node),
+            "class_declaration" | "object_declaration" => self.process_class(node),
+            "function_declaration" => self.get_signature(node),
+            "property_declaration" => self.process_property(node),
+            // Top-level statements of `.kts` scripts, e.g. `plugins { ... }` in Gradle files
+            "call_expression" | "assignment" => self.process_statement(node),
             _ => String::new(),
         }
     }
@@ -55,7 +56,7 @@ impl KotlinSegmenter {
                 let node_text = self.process_node(cursor);
                 if !node_text.trim().is_empty() {
                     result.push_str(&node_text);
-                    result.push_str("\n");
+                    result.push('\n');
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -66,107 +67,172 @@ impl KotlinSegmenter {
         result.trim_end().to_string()
     }
 
-    fn process_class(&self, cursor: &mut TreeCursor) -> String {
-        let node = cursor.node();
-        let class_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnnamedClass".to_string());
-        
-        let mut class_def = format!("class {} {{\n", class_name);
-        
-        if let Some(body) = node.child_by_field_name("body") {
-            for child in body.named_children(&mut body.walk()) {
-                match child.kind() {
-                    "function" => {
-                        let mut child_cursor = child.walk();
-                        let function_def = self.process_node(&mut child_cursor);
-                        class_def.push_str(&function_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
-                    },
-                    "constructor" => {
-                        let mut child_cursor = child.walk();
-                        let constructor_def = self.process_constructor(&mut child_cursor);
-                        class_def.push_str(&constructor_def);
-                    },
-                    "property" => {
-                        class_def.push_str(&self.get_node_text(child));
-                        class_def.push('\n');
-                    },
-                    _ => {}
-                }
-            }
+    fn process_imports(&self, node: Node) -> String {
+        node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "import_header")
+            .map(|import| self.get_node_text(import))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn process_class(&self, node: Node) -> String {
+        let body = match self.get_class_body(node) {
+            Some(body) => body,
+            // Without a body the declaration is all header
+            None => return self.normalize_lines(&self.get_node_text(node)),
+        };
+
+        // Modifiers, type parameters, primary constructor properties and supertypes
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..body.start_byte()]);
+        let mut class_def = format!("{} {{\n", header);
+
+        let members = self.get_class_members(body);
+        let enum_entries: Vec<String> = members.iter()
+            .filter(|child| child.kind() == "enum_entry")
+            .filter_map(|entry| entry.named_child(0))
+            .map(|name| self.get_node_text(name))
+            .collect();
+        if !enum_entries.is_empty() {
+            writeln!(&mut class_def, "    {};", enum_entries.join(", ")).unwrap();
         }
-        
-        class_def.push_str("}\n");
+
+        for &child in &members {
+            let member = match child.kind() {
+                "property_declaration" => self.process_property(child),
+                "function_declaration" | "secondary_constructor" => self.get_signature(child),
+                "class_declaration" | "object_declaration" | "companion_object" => self.process_class(child),
+                "type_alias" => self.get_node_text(child),
+                _ => continue,
+            };
+            class_def.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+
+        class_def.push('}');
         class_def
     }
 
-    fn process_function(&self, cursor: &mut TreeCursor) -> String {
-        let node = cursor.node();
-        let function_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "unnamedFunction".to_string());
-        let return_type = node.child_by_field_name("return_type")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "Unit".to_string());
-        let parameters = node.child_by_field_name("parameters")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "()".to_string());
-        
-        format!("{} {}{};\n", return_type, function_name, parameters)
-    }
-
-    fn process_constructor(&self, cursor: &mut TreeCursor) -> String {
-        let node = cursor.node();
-        let constructor_text = self.get_node_text(node);
-        format!("    // Constructor implementation\n {} ", constructor_text)
+    fn process_statement(&self, node: Node) -> String {
+        let statements = node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "call_suffix")
+            .flat_map(|suffix| suffix.named_children(&mut suffix.walk()).collect::<Vec<Node>>())
+            .filter(|child| child.kind() == "annotated_lambda")
+            .filter_map(|lambda| lambda.named_children(&mut lambda.walk()).find(|child| child.kind() == "lambda_literal"))
+            .find_map(|lambda| lambda.named_children(&mut lambda.walk()).find(|child| child.kind() == "statements"));
+        let statements = match statements {
+            Some(statements) => statements,
+            None => return self.collapse_lambdas(node),
+        };
+
+        // The call up to and including the trailing lambda's parameters
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..statements.start_byte()]);
+        let mut result = format!("{}\n", header);
+        for statement in statements.named_children(&mut statements.walk()) {
+            result.push_str(&self.collapse_lambdas(statement).lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+        result.push('}');
+        result
+    }
+
+    fn collapse_lambdas(&self, node: Node) -> String {
+        self.normalize_lines(&self.replace_lambdas(node))
+    }
+
+    fn replace_lambdas(&self, node: Node) -> String {
+        if node.kind() == "lambda_literal" {
+            return "{ ... }".to_string();
+        }
+        let mut result = String::new();
+        let mut start = node.start_byte();
+        for child in node.children(&mut node.walk()) {
+            result.push_str(&self.source_code[start..child.start_byte()]);
+            result.push_str(&self.replace_lambdas(child));
+            start = child.end_byte();
+        }
+        result.push_str(&self.source_code[start..node.end_byte()]);
+        result
+    }
+
+    fn get_class_body<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let body = node.named_children(&mut node.walk())
+            .find(|child| child.kind() == "class_body" || child.kind() == "enum_class_body");
+        // With `: Repo by repo { ... }` the grammar reads the body as a lambda passed to `repo`
+        body.or_else(|| {
+            node.named_children(&mut node.walk())
+                .filter(|child| child.kind() == "delegation_specifier")
+                .filter_map(|specifier| specifier.named_child(0).filter(|delegation| delegation.kind() == "explicit_delegation"))
+                .filter_map(|delegation| delegation.named_children(&mut delegation.walk()).find(|child| child.kind() == "call_expression"))
+                .filter_map(|call| call.named_children(&mut call.walk()).find(|child| child.kind() == "call_suffix"))
+                .filter_map(|suffix| suffix.named_children(&mut suffix.walk()).find(|child| child.kind() == "annotated_lambda"))
+                .find_map(|lambda| lambda.named_children(&mut lambda.walk()).find(|child| child.kind() == "lambda_literal"))
+        })
+    }
+
+    fn get_class_members<'a>(&self, body: Node<'a>) -> Vec<Node<'a>> {
+        let container = match body.kind() {
+            "lambda_literal" => body.named_children(&mut body.walk()).find(|child| child.kind() == "statements"),
+            _ => Some(body),
+        };
+        container.map(|container| container.named_children(&mut container.walk()).collect()).unwrap_or_default()
+    }
+
+    fn process_property(&self, node: Node) -> String {
+        let is_const = node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "modifiers")
+            .any(|modifiers| self.get_node_text(modifiers).split_whitespace().any(|modifier| modifier == "const"));
+        if is_const {
+            return self.normalize_lines(&self.get_node_text(node));
+        }
+
+        // Drop initialisers, delegates and accessors, the declared type is what matters
+        let end = node.children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "=" | "property_delegate" | "getter" | "setter"))
+            .map(|child| child.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.normalize_lines(&self.source_code[node.start_byte()..end])
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Covers `suspend`, extension receivers and expression bodies alike
+        let end = node.named_children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "function_body" | "constructor_delegation_call" | "statements"))
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let signature = self.source_code[node.start_byte()..end].trim_end().trim_end_matches(':');
+        self.normalize_lines(signature)
+    }
+
+    fn normalize_lines(&self, text: &str) -> String {
+        // Annotations on their own lines keep the member's original indentation otherwise
+        text.trim().lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
     }
 
     fn get_node_text(&self, node: Node) -> String {
         self.source_code[node.start_byte()..node.end_byte()].to_string()
     }
 
-    #[allow(dead_code)]
-    fn process_node_func_class(&self, cursor: &mut TreeCursor) -> String {
-        let mut result = String::new();
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
 
         loop {
             let node = cursor.node();
 
-            match node.kind() {
-                "function" | "constructor" => {
-                    let start_line = node.start_position().row;
-                    let end_line = node.end_position().row;
-
-                    writeln!(&mut result, "// Code for: {}", self.get_line(start_line)).unwrap();
-                    writeln!(&mut result, "{} {{ ... }}", self.get_signature(node)).unwrap();
-                    writeln!(&mut result, "// End: {}", self.get_line(end_line)).unwrap();
-                    writeln!(&mut result).unwrap();
-
-                    cursor.goto_first_child();
-                    while cursor.goto_next_sibling() {}
-                    cursor.goto_parent();
-                },
-                "class" => {
-                    let class_text = self.get_node_text(node.child(0).unwrap_or(node));
-                    writeln!(&mut result, "{} {{", class_text).unwrap();
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
                         cursor.goto_parent();
                     }
-                    writeln!(&mut result, "}}").unwrap();
+                    symbols.push(symbol);
                 },
-                "file" => {
+                None => {
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
                         cursor.goto_parent();
                     }
                 },
-                _ => {
-                    if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
-                        cursor.goto_parent();
-                    }
-                }
             }
 
             if !cursor.goto_next_sibling() {
@@ -174,14 +240,29 @@ impl KotlinSegmenter {
             }
         }
 
-        result
-    }
-
-    fn get_line(&self, line_number: usize) -> &str {
-        self.source_code.lines().nth(line_number).unwrap_or("")
+        symbols
     }
 
-    fn get_signature(&self, node: Node) -> String {
-        self.get_node_text(node.named_child(0).unwrap_or(node))
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "function_declaration" => "function",
+            "secondary_constructor" => return Some(("constructor", "constructor".to_string())),
+            "object_declaration" => "object",
+            "companion_object" => {
+                // Unnamed companions are referred to as `Companion`
+                let name = node.named_children(&mut node.walk())
+                    .find(|child| child.kind() == "type_identifier")
+                    .map(|name| self.get_node_text(name))
+                    .unwrap_or_else(|| "Companion".to_string());
+                return Some(("object", name));
+            },
+            "class_declaration" if node.children(&mut node.walk()).any(|child| child.kind() == "interface") => "interface",
+            "class_declaration" if self.get_class_body(node).is_some_and(|body| body.kind() == "enum_class_body") => "enum",
+            "class_declaration" => "class",
+            _ => return None,
+        };
+        let name = node.named_children(&mut node.walk())
+            .find(|child| matches!(child.kind(), "simple_identifier" | "type_identifier"))?;
+        Some((kind, self.get_node_text(name)))
     }
 }
@@ -0,0 +1,193 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_lua::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct LuaSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for LuaSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func(&mut cursor, "", false)
+    }
+}
+
+impl LuaSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Lua grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Lua code");
+
+        Box::new(LuaSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "chunk" => self.process_chunk(cursor),
+            "function_declaration" => self.process_function(cursor),
+            "variable_declaration" | "assignment_statement" => self.process_assignment(cursor),
+            // `return M` tells which table the module exports
+            "return_statement" => self.get_node_text(node),
+            _ => String::new(),
+        }
+    }
+
+    fn process_chunk(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{} end", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        let end = node.child_by_field_name("parameters")
+            .map(|params| params.end_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].to_string()
+    }
+
+    fn process_assignment(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        // `local x = ...` wraps the assignment in a variable declaration
+        let (prefix, assignment) = match node.kind() {
+            "variable_declaration" => match node.named_child(0) {
+                Some(assignment) if assignment.kind() == "assignment_statement" => ("local ", assignment),
+                _ => return self.get_node_text(node),
+            },
+            _ => ("", node),
+        };
+
+        let names = assignment.named_children(&mut assignment.walk())
+            .find(|child| child.kind() == "variable_list")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+        let values: Vec<Node> = assignment.named_children(&mut assignment.walk())
+            .find(|child| child.kind() == "expression_list")
+            .map(|list| list.named_children(&mut list.walk()).collect())
+            .unwrap_or_default();
+
+        let value = match values.as_slice() {
+            [value] => match value.kind() {
+                "function_definition" => format!("{} end", self.get_signature(*value)),
+                "table_constructor" => self.process_table(*value),
+                _ => self.get_single_line(*value),
+            },
+            _ => values.iter().map(|value| self.get_single_line(*value)).collect::<Vec<String>>().join(", "),
+        };
+
+        format!("{}{} = {}", prefix, names, value)
+    }
+
+    fn process_table(&self, node: Node) -> String {
+        let fields: Vec<String> = node.named_children(&mut node.walk())
+            .filter(|field| field.kind() == "field")
+            .map(|field| {
+                let value = field.child_by_field_name("value");
+                match (field.child_by_field_name("name"), value) {
+                    (Some(name), Some(value)) if value.kind() == "function_definition" => {
+                        format!("{} = {} end", self.get_node_text(name), self.get_signature(value))
+                    },
+                    (Some(name), _) => self.get_node_text(name),
+                    (None, _) => self.get_single_line(field),
+                }
+            })
+            .collect();
+
+        if fields.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{\n{}}}", fields.iter().map(|field| format!("    {},\n", field)).collect::<String>())
+        }
+    }
+
+    fn get_single_line(&self, node: Node) -> String {
+        let text = self.get_node_text(node);
+        if text.contains('\n') {
+            "...".to_string()
+        } else {
+            text
+        }
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        match node.kind() {
+            // `function M.foo()` and `function M:bar()` already carry their table
+            "function_declaration" => Some(("function", self.get_node_text(node.child_by_field_name("name")?))),
+            "function_definition" => {
+                // Anonymous functions are only named by what they are assigned to
+                let parent = node.parent()?;
+                let name = match parent.kind() {
+                    "field" => parent.child_by_field_name("name")?,
+                    "expression_list" => parent.parent()?.named_child(0)?.named_child(0)?,
+                    _ => return None,
+                };
+                Some(("function", self.get_node_text(name)))
+            },
+            _ => None,
+        }
+    }
+}
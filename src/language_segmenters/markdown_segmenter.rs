@@ -0,0 +1,170 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_md::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, qualify_name};
+
+pub struct MarkdownSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for MarkdownSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        let mut awaiting_summary = false;
+        self.process_node(&mut cursor, &mut awaiting_summary).trim_end().to_string()
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_headings(&mut cursor, "")
+    }
+}
+
+impl MarkdownSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Markdown grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Markdown");
+
+        Box::new(MarkdownSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor, awaiting_summary: &mut bool) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "document" | "section" => self.process_children(cursor, awaiting_summary),
+            "atx_heading" | "setext_heading" => {
+                // The next paragraph is the section's summary
+                *awaiting_summary = true;
+                self.process_heading(node)
+            },
+            "paragraph" if *awaiting_summary => {
+                *awaiting_summary = false;
+                self.get_first_sentence(node)
+            },
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor, awaiting_summary: &mut bool) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor, awaiting_summary);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        // Nested sections would otherwise stack their trailing newlines
+        result.trim_end().to_string()
+    }
+
+    fn process_heading(&self, node: Node) -> String {
+        format!("{} {}", "#".repeat(self.get_heading_level(node)), self.get_heading_text(node))
+    }
+
+    fn get_heading_level(&self, node: Node) -> usize {
+        node.children(&mut node.walk())
+            .find_map(|child| match child.kind() {
+                "atx_h1_marker" | "setext_h1_underline" => Some(1),
+                "atx_h2_marker" | "setext_h2_underline" => Some(2),
+                "atx_h3_marker" => Some(3),
+                "atx_h4_marker" => Some(4),
+                "atx_h5_marker" => Some(5),
+                "atx_h6_marker" => Some(6),
+                _ => None,
+            })
+            .unwrap_or(1)
+    }
+
+    fn get_heading_text(&self, node: Node) -> String {
+        node.child_by_field_name("heading_content")
+            .map(|n| self.collapse_whitespace(&self.get_node_text(n)))
+            .unwrap_or_default()
+    }
+
+    fn get_first_sentence(&self, node: Node) -> String {
+        let paragraph = self.collapse_whitespace(&self.get_node_text(node));
+        let end = paragraph.char_indices()
+            .find(|&(i, c)| matches!(c, '.' | '!' | '?') && paragraph[i + 1..].starts_with(' '))
+            .map(|(i, _)| i + 1)
+            .unwrap_or(paragraph.len());
+        paragraph[..end].to_string()
+    }
+
+    fn collapse_whitespace(&self, text: &str) -> String {
+        text.split_whitespace().collect::<Vec<&str>>().join(" ")
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_headings(&self, cursor: &mut TreeCursor, scope: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let qualified_name = qualify_name(scope, &name, " > ");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    symbol.end_line = self.get_end_line(node);
+                    // The heading is the signature and the section's first sentence its summary
+                    if let Some(heading) = node.named_child(0) {
+                        symbol.signature = self.process_heading(heading);
+                    }
+                    symbol.doc = node.named_children(&mut node.walk())
+                        .find(|child| child.kind() == "paragraph")
+                        .map(|paragraph| self.get_first_sentence(paragraph));
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_headings(cursor, &qualified_name);
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_headings(cursor, scope));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        // Sections span from their heading to the next heading of the same or a higher level
+        if node.kind() != "section" {
+            return None;
+        }
+        let heading = node.named_child(0).filter(|child| matches!(child.kind(), "atx_heading" | "setext_heading"))?;
+        Some(("section", self.get_heading_text(heading)))
+    }
+
+    fn get_end_line(&self, node: Node) -> usize {
+        // Sections own the line break after their last line
+        let end = node.end_position();
+        if end.column == 0 && end.row > node.start_position().row {
+            end.row
+        } else {
+            end.row + 1
+        }
+    }
+}
@@ -1,35 +1,53 @@
 mod python_segmenter;
 mod javascript_segmenter;
-// mod cobol_segmenter;
-// mod c_segmenter;
-// mod cpp_segmenter;
-// mod csharp_segmenter;
-// mod ruby_segmenter;
-// mod scala_segmenter;
+mod cobol_segmenter;
+mod c_segmenter;
+mod cpp_segmenter;
+mod csharp_segmenter;
+mod ruby_segmenter;
+mod scala_segmenter;
 mod rust_segmenter;
-// mod go_segmenter;
-// mod kotlin_segmenter;
-// mod lua_segmenter;
-// mod perl_segmenter;
+mod go_segmenter;
+mod kotlin_segmenter;
+mod lua_segmenter;
+mod perl_segmenter;
 mod typescript_segmenter;
 mod java_segmenter;
-// mod php_segmenter;
-// mod elixir_segmenter;
+mod swift_segmenter;
+mod objc_segmenter;
+mod header_segmenter;
+mod r_segmenter;
+mod bash_segmenter;
+mod php_segmenter;
+mod elixir_segmenter;
+mod erlang_segmenter;
+mod markdown_segmenter;
+mod html_segmenter;
+mod json_segmenter;
 
 pub use python_segmenter::PythonSegmenter;
 pub use javascript_segmenter::JavaScriptSegmenter;
-// pub use cobol_segmenter::CobolSegmenter;
-// pub use c_segmenter::CSegmenter;
-// pub use cpp_segmenter::CppSegmenter;
-// pub use csharp_segmenter::CSharpSegmenter;
-// pub use ruby_segmenter::RubySegmenter;
-// pub use scala_segmenter::ScalaSegmenter;
+pub use cobol_segmenter::CobolSegmenter;
+pub use c_segmenter::CSegmenter;
+pub use cpp_segmenter::CppSegmenter;
+pub use csharp_segmenter::CSharpSegmenter;
+pub use ruby_segmenter::RubySegmenter;
+pub use scala_segmenter::ScalaSegmenter;
 pub use rust_segmenter::RustSegmenter;
-// pub use go_segmenter::GoSegmenter;
-// pub use kotlin_segmenter::KotlinSegmenter;
-// pub use lua_segmenter::LuaSegmenter;
-// pub use perl_segmenter::PerlSegmenter;
+pub use go_segmenter::GoSegmenter;
+pub use kotlin_segmenter::KotlinSegmenter;
+pub use lua_segmenter::LuaSegmenter;
+pub use perl_segmenter::PerlSegmenter;
 pub use typescript_segmenter::TypeScriptSegmenter;
 pub use java_segmenter::JavaSegmenter;
-// pub use php_segmenter::PhpSegmenter;
-// pub use elixir_segmenter::ElixirSegmenter;
+pub use swift_segmenter::SwiftSegmenter;
+pub use objc_segmenter::ObjcSegmenter;
+pub use header_segmenter::HeaderSegmenter;
+pub use r_segmenter::RSegmenter;
+pub use bash_segmenter::BashSegmenter;
+pub use php_segmenter::PhpSegmenter;
+pub use elixir_segmenter::ElixirSegmenter;
+pub use erlang_segmenter::ErlangSegmenter;
+pub use markdown_segmenter::MarkdownSegmenter;
+pub use html_segmenter::HtmlSegmenter;
+pub use json_segmenter::JsonSegmenter;
@@ -0,0 +1,191 @@
+use std::fmt::Write;
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_objc::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct ObjcSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for ObjcSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
+    }
+}
+
+impl ObjcSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Objective-C grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Objective-C code");
+
+        Box::new(ObjcSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "translation_unit" => self.process_children(cursor),
+            "preproc_if" | "preproc_ifdef" | "preproc_else" | "preproc_elif" => self.process_children(cursor),
+            "preproc_include" | "preproc_def" => self.get_node_text(node).trim_end().to_string(),
+            "class_declaration" | "protocol_forward_declaration" => self.get_node_text(node),
+            // Interfaces and protocols are already declarations, keep them whole
+            "class_interface" | "protocol_declaration" => self.get_node_text(node),
+            "class_implementation" | "category_implementation" => self.process_implementation(cursor),
+            "type_definition" | "declaration" => self.get_node_text(node),
+            "function_definition" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_implementation(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let definitions: Vec<Node> = node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "implementation_definition")
+            .collect();
+
+        let header_end = definitions.first()
+            .map(|definition| definition.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let header = self.source_code[node.start_byte()..header_end].trim_end().trim_end_matches("@end").trim_end();
+        let mut implementation_def = format!("{}\n", header);
+
+        for definition in definitions {
+            for child in definition.named_children(&mut definition.walk()) {
+                match child.kind() {
+                    "method_definition" | "function_definition" => {
+                        writeln!(implementation_def, "{};", self.get_signature(child)).unwrap();
+                    },
+                    "property_implementation" => {
+                        writeln!(implementation_def, "{}", self.get_node_text(child)).unwrap();
+                    },
+                    _ => {}
+                }
+            }
+        }
+
+        implementation_def.push_str("@end");
+        implementation_def
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{};", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Method definitions have no body field, their body is the compound statement
+        let end = node.children(&mut node.walk())
+            .find(|child| child.kind() == "compound_statement")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].trim_end().to_string()
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "method_declaration" | "method_definition" => return Some(("method", self.get_selector(node))),
+            "function_definition" => {
+                let mut declarator = node.child_by_field_name("declarator")?;
+                while let Some(inner) = declarator.child_by_field_name("declarator") {
+                    declarator = inner;
+                }
+                return Some(("function", self.get_node_text(declarator)));
+            },
+            "class_interface" => "interface",
+            "class_implementation" => "implementation",
+            "category_interface" | "category_implementation" => "category",
+            "protocol_declaration" => "protocol",
+            _ => return None,
+        };
+        // The class name comes first, followed by the category name if there is one
+        let names: Vec<String> = node.children(&mut node.walk())
+            .filter(|child| child.kind() == "identifier")
+            .take(2)
+            .map(|name| self.get_node_text(name))
+            .collect();
+        match (kind, names.as_slice()) {
+            ("category", [class_name, category_name]) => Some((kind, format!("{}({})", class_name, category_name))),
+            (_, [name, ..]) => Some((kind, name.clone())),
+            _ => None,
+        }
+    }
+
+    fn get_selector(&self, node: Node) -> String {
+        // Selector keywords are the identifiers in front of each parameter
+        let mut selector = String::new();
+        for child in node.children(&mut node.walk()) {
+            match child.kind() {
+                "identifier" => selector.push_str(&self.get_node_text(child)),
+                "method_parameter" => selector.push(':'),
+                _ => {},
+            }
+        }
+        selector
+    }
+}
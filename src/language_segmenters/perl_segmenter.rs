@@ -0,0 +1,231 @@
+use std::fmt::Write;
+use crate::code_segmenter::{CodeSegmenter, Symbol, first_sentence, qualify_name};
+
+// There is no Perl grammar for our tree-sitter version, and the language can't be
+// parsed without running it anyway, so declarations are picked out line by line.
+pub struct PerlSegmenter {
+    source_code: String,
+}
+
+impl CodeSegmenter for PerlSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut result = String::new();
+        for (_, line) in self.code_lines() {
+            let trimmed = line.trim();
+            if self.is_package(trimmed) {
+                // `package Foo { ... }` blocks are flattened to the statement form
+                writeln!(&mut result, "package {};", self.get_package_name(trimmed)).unwrap();
+            } else if self.is_dependency(trimmed) {
+                result.push_str(trimmed);
+                result.push('\n');
+            } else if let Some(sub_name) = self.get_sub_name(trimmed) {
+                writeln!(&mut result, "sub {} {{ ... }}", sub_name).unwrap();
+            }
+        }
+        result.trim_end().to_string()
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let lines = self.code_lines();
+        let last_line = lines.last().map(|(line_number, _)| line_number + 1).unwrap_or(1);
+        let mut packages: Vec<(String, usize)> = Vec::new();
+        let mut subs: Vec<Vec<Symbol>> = vec![Vec::new()];
+
+        for (i, (line_number, line)) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if self.is_package(trimmed) {
+                packages.push((self.get_package_name(trimmed), line_number + 1));
+                subs.push(Vec::new());
+            } else if let Some(sub_name) = self.get_sub_name(trimmed) {
+                let package = packages.last().map(|(name, _)| name.as_str()).unwrap_or("main");
+                let qualified_name = if sub_name.contains("::") {
+                    sub_name.to_string()
+                } else {
+                    qualify_name(package, sub_name, "::")
+                };
+                // A sub can't outlast its package, which ends where the next one starts
+                let package_end = lines[i..].iter()
+                    .position(|(_, line)| self.is_package(line.trim()))
+                    .map_or(lines.len(), |offset| i + offset);
+                let end_line = self.find_block_end(&lines[i..package_end]);
+                let mut symbol = Symbol::from_lines("function", sub_name, &qualified_name, line_number + 1, end_line, &self.source_code);
+                symbol.signature = format!("sub {}", sub_name);
+                symbol.visibility = Some(if sub_name.starts_with('_') { "private" } else { "public" }.to_string());
+                symbol.doc = self.get_comment(&lines[..i]);
+                subs.last_mut().unwrap().push(symbol);
+            }
+        }
+
+        // Subs before the first package statement belong to `main`
+        let mut subs = subs.into_iter();
+        let mut symbols = subs.next().unwrap_or_default();
+        for (index, ((name, start_line), children)) in packages.iter().zip(subs).enumerate() {
+            // A package statement lasts until the next one
+            let end_line = packages.get(index + 1).map(|(_, next_start)| next_start - 1).unwrap_or(last_line);
+            let mut package = Symbol::from_lines("package", name, name, *start_line, end_line, &self.source_code);
+            package.children = children;
+            symbols.push(package);
+        }
+        symbols
+    }
+}
+
+impl PerlSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        Box::new(PerlSegmenter {
+            source_code: code,
+        })
+    }
+
+    fn code_lines(&self) -> Vec<(usize, &str)> {
+        let mut lines = Vec::new();
+        let mut in_pod = false;
+        for (line_number, line) in self.source_code.lines().enumerate() {
+            // POD blocks run from any `=directive` at the start of a line until `=cut`
+            if line.starts_with('=') && line.len() > 1 && line.as_bytes()[1].is_ascii_alphabetic() {
+                in_pod = !line.starts_with("=cut");
+                continue;
+            }
+            if line == "__END__" || line == "__DATA__" {
+                break;
+            }
+            if !in_pod {
+                lines.push((line_number, line));
+            }
+        }
+        lines
+    }
+
+    fn is_package(&self, line: &str) -> bool {
+        line.starts_with("package ")
+    }
+
+    fn get_package_name(&self, line: &str) -> String {
+        line["package ".len()..]
+            .split(|c: char| c.is_whitespace() || c == ';' || c == '{')
+            .find(|part| !part.is_empty())
+            .unwrap_or("main")
+            .to_string()
+    }
+
+    fn is_dependency(&self, line: &str) -> bool {
+        ["use ", "no ", "require ", "our @ISA", "@ISA"].iter().any(|prefix| line.starts_with(prefix))
+    }
+
+    fn get_sub_name<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let rest = line.strip_prefix("sub ")?.trim_start();
+        let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).unwrap_or(rest.len());
+        // `sub {` is an anonymous sub assigned somewhere else
+        if end == 0 {
+            None
+        } else {
+            Some(&rest[..end])
+        }
+    }
+
+    fn get_comment(&self, preceding_lines: &[(usize, &str)]) -> Option<String> {
+        let comment: Vec<&str> = preceding_lines.iter()
+            .rev()
+            .map(|(_, line)| line.trim())
+            .take_while(|line| line.starts_with('#'))
+            .map(|line| line.trim_start_matches('#').trim())
+            .collect();
+        first_sentence(&comment.into_iter().rev().collect::<Vec<&str>>().join(" "))
+    }
+
+    fn find_block_end(&self, lines: &[(usize, &str)]) -> usize {
+        // Counts braces outside of strings, comments and quote-like operators, which
+        // is good enough for the body of a sub
+        let chars: Vec<(usize, char)> = lines.iter()
+            .flat_map(|(line_number, line)| line.chars().chain(std::iter::once('\n')).map(move |c| (*line_number, c)))
+            .collect();
+        let mut depth = 0;
+        let mut opened = false;
+        let mut i = 0;
+        while i < chars.len() {
+            let (line_number, c) = chars[i];
+            let previous = if i > 0 { chars[i - 1].1 } else { ' ' };
+            match c {
+                '\\' => i += 1,
+                // `$#array` is the last index, not a comment
+                '#' if previous != '$' => {
+                    while i + 1 < chars.len() && chars[i + 1].1 != '\n' {
+                        i += 1;
+                    }
+                },
+                '"' | '\'' | '`' => i = self.skip_delimited(&chars, i, 1),
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                },
+                '}' => depth -= 1,
+                // Forward declarations like `sub name;` have no body
+                ';' if !opened => return line_number + 1,
+                c if c.is_ascii_lowercase() && !(previous.is_alphanumeric() || "_$@%&-:>".contains(previous)) => {
+                    let word: String = chars[i..].iter().map(|(_, c)| *c).take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                    let parts = match word.as_str() {
+                        "m" | "q" | "qq" | "qr" | "qw" | "qx" => 1,
+                        "s" | "tr" | "y" => 2,
+                        _ => 0,
+                    };
+                    let delimiter = chars[i + word.len()..].iter().position(|(_, c)| !c.is_whitespace()).map(|offset| i + word.len() + offset);
+                    match delimiter {
+                        // `s => 1`, `$h{s}` and `y = 2` are no operators
+                        Some(start) if parts > 0 && !chars[start].1.is_alphanumeric() && !"=,;)]}>".contains(chars[start].1) => {
+                            i = self.skip_delimited(&chars, start, parts);
+                        },
+                        _ => i += word.len() - 1,
+                    }
+                },
+                _ => {},
+            }
+            if opened && depth == 0 {
+                return line_number + 1;
+            }
+            i += 1;
+        }
+        lines.last().map(|(line_number, _)| line_number + 1).unwrap_or(1)
+    }
+
+    /// Index of the delimiter closing the last of `parts`, e.g. 2 for `s{a}{b}` or `s/a/b/`.
+    fn skip_delimited(&self, chars: &[(usize, char)], start: usize, parts: usize) -> usize {
+        let mut open = chars[start].1;
+        let mut parts = parts;
+        let mut nesting = 0;
+        let mut i = start + 1;
+        while i < chars.len() {
+            let close = match open {
+                '{' => '}',
+                '(' => ')',
+                '[' => ']',
+                '<' => '>',
+                open => open,
+            };
+            let c = chars[i].1;
+            if c == '\\' {
+                i += 1;
+            } else if c == close && nesting == 0 {
+                parts -= 1;
+                if parts == 0 {
+                    return i;
+                }
+                // Bracketed parts each have their own pair, `s{a} {b}`
+                if open != close {
+                    match chars[i + 1..].iter().position(|(_, c)| !c.is_whitespace()) {
+                        Some(offset) => {
+                            i += 1 + offset;
+                            open = chars[i].1;
+                        },
+                        None => return chars.len() - 1,
+                    }
+                }
+            } else if c == open && open != close {
+                nesting += 1;
+            } else if c == close {
+                nesting -= 1;
+            }
+            i += 1;
+        }
+        chars.len() - 1
+    }
+}
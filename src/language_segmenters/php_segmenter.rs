@@ -0,0 +1,229 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_php::language_php;
+use crate::code_segmenter::{CodeSegmenter, Symbol, qualify_name};
+
+pub struct PhpSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for PhpSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func(&mut cursor, "")
+    }
+}
+
+impl PhpSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language_php()).expect("Error loading PHP grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse PHP code");
+
+        Box::new(PhpSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "program" => self.process_program(cursor),
+            "namespace_definition" => self.process_namespace(cursor),
+            "namespace_use_declaration" | "const_declaration" | "declare_statement" => self.get_node_text(node),
+            "expression_statement" => self.process_expression_statement(cursor),
+            "class_declaration" | "interface_declaration" | "trait_declaration" | "enum_declaration" => self.process_type(node),
+            "function_definition" => format!("{};", self.get_signature(node)),
+            _ => String::new(),
+        }
+    }
+
+    fn process_program(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_namespace(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            // `namespace Foo;` applies to the rest of the file
+            None => return self.get_node_text(node),
+        };
+
+        let mut namespace_def = format!("{} {{\n", self.source_code[node.start_byte()..body.start_byte()].trim_end());
+        let mut body_cursor = body.walk();
+        let members = self.process_program(&mut body_cursor);
+        namespace_def.push_str(&members.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        namespace_def.push('}');
+        namespace_def
+    }
+
+    fn process_expression_statement(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.named_child(0).map(|expression| expression.kind()) {
+            Some("require_expression" | "require_once_expression" | "include_expression" | "include_once_expression") => {
+                self.get_node_text(node)
+            },
+            _ => String::new(),
+        }
+    }
+
+    fn process_type(&self, node: Node) -> String {
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        // Attributes, modifiers, backed enum type, extends and implements
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..body.start_byte()]);
+        let mut type_def = format!("{} {{\n", header);
+
+        for child in body.named_children(&mut body.walk()) {
+            let member = match child.kind() {
+                "use_declaration" | "const_declaration" | "enum_case" => self.normalize_lines(&self.get_node_text(child)),
+                "property_declaration" => self.process_property(child),
+                "method_declaration" => self.process_method(child),
+                _ => continue,
+            };
+            type_def.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+
+        type_def.push('}');
+        type_def
+    }
+
+    fn process_property(&self, node: Node) -> String {
+        let elements: Vec<Node> = node.named_children(&mut node.walk())
+            .filter(|child| child.kind() == "property_element")
+            .collect();
+        let first_element = match elements.first() {
+            Some(element) => *element,
+            None => return self.normalize_lines(&self.get_node_text(node)),
+        };
+
+        // Keep modifiers, type and names, initialisers can be arbitrarily large arrays
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..first_element.start_byte()]);
+        let names: Vec<String> = elements.iter()
+            .filter_map(|element| element.named_child(0))
+            .map(|name| self.get_node_text(name))
+            .collect();
+        format!("{} {};", header, names.join(", "))
+    }
+
+    fn process_method(&self, node: Node) -> String {
+        if node.child_by_field_name("body").is_some() {
+            format!("{};", self.get_signature(node))
+        } else {
+            self.normalize_lines(&self.get_node_text(node))
+        }
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.normalize_lines(&self.source_code[node.start_byte()..end])
+    }
+
+    fn normalize_lines(&self, text: &str) -> String {
+        // Attributes and promoted constructor parameters keep the member's original indentation otherwise
+        text.trim().lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func(&self, cursor: &mut TreeCursor, scope: &str) -> Vec<Symbol> {
+        let mut symbols: Vec<Symbol> = Vec::new();
+        // `namespace Foo;` applies to every declaration after it
+        let mut scope = scope.to_string();
+        let mut namespace: Option<usize> = None;
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let qualified_name = match kind {
+                        "namespace" => name.clone(),
+                        "method" => qualify_name(&scope, &name, "::"),
+                        _ => qualify_name(&scope, &name, "\\"),
+                    };
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func(cursor, &qualified_name);
+                        cursor.goto_parent();
+                    }
+                    match namespace {
+                        Some(index) if kind != "namespace" => symbols[index].children.push(symbol),
+                        _ => symbols.push(symbol),
+                    }
+                    if kind == "namespace" && node.child_by_field_name("body").is_none() {
+                        scope = qualified_name;
+                        namespace = Some(symbols.len() - 1);
+                    }
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        let children = self.process_node_func(cursor, &scope);
+                        match namespace {
+                            Some(index) => symbols[index].children.extend(children),
+                            None => symbols.extend(children),
+                        }
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        // A namespace statement spans the declarations it applies to
+        if let Some(index) = namespace {
+            if let Some(last_child) = symbols[index].children.last() {
+                let (end_byte, end_line) = (last_child.byte_range.end, last_child.end_line);
+                symbols[index].byte_range.end = end_byte;
+                symbols[index].end_line = end_line;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "namespace_definition" => "namespace",
+            "class_declaration" => "class",
+            "interface_declaration" => "interface",
+            "trait_declaration" => "trait",
+            "enum_declaration" => "enum",
+            "function_definition" => "function",
+            "method_declaration" => "method",
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+    }
+}
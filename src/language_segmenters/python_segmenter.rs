@@ -1,7 +1,7 @@
 use std::fmt::Write;
 use tree_sitter::{Parser, Node, TreeCursor};
 use tree_sitter_python::language;
-use crate::code_segmenter::CodeSegmenter;
+use crate::code_segmenter::{CodeSegmenter, Symbol, first_sentence, is_type_kind, qualify_name};
 
 pub struct PythonSegmenter {
     tree: tree_sitter::Tree,
@@ -14,10 +14,9 @@ impl CodeSegmenter for PythonSegmenter {
         self.process_node(&mut cursor)
     }
 
-    fn extract_functions_classes(&self) -> String {
-        String::new()
-        // let mut cursor = self.tree.walk();
-        // self.process_node_func_class(&mut cursor)
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
     }
 }
 
@@ -41,7 +40,10 @@ impl PythonSegmenter {
             "function_definition" => self.process_function(cursor),
             "async_function_definition" => self.process_function(cursor),
             "decorated_definition" => self.process_decorated_definition(cursor),
-            _ => self.get_node_text(node),
+            "import_statement" | "import_from_statement" | "future_import_statement" => self.get_node_text(node),
+            "expression_statement" => self.process_module_statement(node),
+            "if_statement" => self.process_if(node),
+            _ => String::new(),
         }
     }
 
@@ -52,7 +54,7 @@ impl PythonSegmenter {
                 let node_text = self.process_node(cursor);
                 if !node_text.trim().is_empty() {
                     result.push_str(&node_text);
-                    result.push_str("\n");
+                    result.push('\n');
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -85,103 +87,180 @@ impl PythonSegmenter {
         result
     }
 
+    fn process_module_statement(&self, node: Node) -> String {
+        if let Some(docstring) = self.get_docstring(node) {
+            return docstring;
+        }
+        // Only `__all__`, dunders like `__version__` and constants describe the module
+        match node.named_child(0) {
+            Some(assignment) if assignment.kind() == "assignment" => {
+                let target = assignment.child_by_field_name("left")
+                    .map(|n| self.get_node_text(n))
+                    .unwrap_or_default();
+                let is_dunder = target.starts_with("__") && target.ends_with("__");
+                let is_constant = target.chars().any(|c| c.is_ascii_uppercase())
+                    && target.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+                if is_dunder || is_constant {
+                    self.process_assignment(assignment)
+                } else {
+                    String::new()
+                }
+            },
+            _ => String::new(),
+        }
+    }
+
+    fn process_assignment(&self, assignment: Node) -> String {
+        let text = self.get_node_text(assignment);
+        let is_all = assignment.child_by_field_name("left").is_some_and(|target| self.get_node_text(target) == "__all__");
+        match assignment.child_by_field_name("right") {
+            // Multi-line literals only keep their name and annotation, `__all__` is the public API though
+            Some(value) if text.contains('\n') && !is_all => {
+                format!("{} ...", self.source_code[assignment.start_byte()..value.start_byte()].trim_end())
+            },
+            _ => text,
+        }
+    }
+
+    fn process_if(&self, node: Node) -> String {
+        let condition = node.child_by_field_name("condition")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+        let condition = condition.replace('\'', "\"");
+        if condition == "__name__ == \"__main__\"" {
+            format!("{}:\n    ...", self.get_node_text(node).lines().next().unwrap_or("").trim_end_matches(':'))
+        } else {
+            String::new()
+        }
+    }
+
     fn process_class(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let class_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnnamedClass".to_string());
-        
-        let mut class_def = format!("class {}:\n", class_name);
+        // Bases, generics and `metaclass=` keywords
+        let mut class_def = format!("{}\n", self.get_header(node));
+        let mut members = String::new();
         
         if let Some(body) = node.child_by_field_name("body") {
-            for child in body.children(&mut body.walk()) {
-                match child.kind() {
+            for child in body.named_children(&mut body.walk()) {
+                let member = match child.kind() {
                     "function_definition" | "async_function_definition" => {
                         let mut child_cursor = child.walk();
-                        let method_def = self.process_function(&mut child_cursor);
-                        class_def.push_str(&method_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+                        self.process_function(&mut child_cursor)
                     },
                     "decorated_definition" => {
                         let mut child_cursor = child.walk();
-                        let decorated_def = self.process_decorated_definition(&mut child_cursor);
-                        class_def.push_str(&decorated_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+                        self.process_decorated_definition(&mut child_cursor)
                     },
-                    _ => {}
-                }
+                    "class_definition" => {
+                        let mut child_cursor = child.walk();
+                        self.process_class(&mut child_cursor)
+                    },
+                    "expression_statement" => match (self.get_docstring(child), child.named_child(0)) {
+                        (Some(docstring), _) => docstring,
+                        // Class attributes and dataclass fields
+                        (None, Some(assignment)) if assignment.kind() == "assignment" => self.process_assignment(assignment),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+                members.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
             }
         }
-        
-        if !class_def.ends_with("\n") {
-            class_def.push('\n');
+
+        if members.is_empty() {
+            members.push_str("    pass\n");
         }
-        
+        class_def.push_str(&members);
         class_def
     }
+
     fn process_function(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let is_async = node.children(&mut node.walk()).any(|child| child.kind() == "async");
         let func_name = self.get_node_text(node.child_by_field_name("name").unwrap());
-        let params = self.get_node_text(node.child_by_field_name("parameters").unwrap());
-        
-        let mut func_def = if is_async {
-            format!("async def {}{}:\n", func_name, params)
-        } else {
-            format!("def {}{}:\n", func_name, params)
-        };
+        // `async`, parameters and the return annotation
+        let mut func_def = format!("{}\n", self.get_header(node));
+
+        let body = node.child_by_field_name("body");
+        let docstring = body
+            .and_then(|body| body.named_child(0))
+            .and_then(|statement| self.get_docstring(statement));
+        if let Some(docstring) = &docstring {
+            writeln!(func_def, "    {}", docstring).unwrap();
+        }
 
         if func_name == "__init__" {
             // Keep the entire __init__ method intact
-            if let Some(body) = node.child_by_field_name("body") {
+            if let Some(body) = body {
+                // The first line starts at the body, the rest still carry the source indentation
+                let body_indent = " ".repeat(body.start_position().column);
                 let body_text = self.get_node_text(body);
                 for line in body_text.lines() { 
-                    writeln!(func_def, "    {}", line).unwrap();
+                    writeln!(func_def, "    {}", line.strip_prefix(&body_indent).unwrap_or(line)).unwrap();
                 }
             }
-        } else {
+        } else if docstring.is_none() {
             func_def.push_str("    pass\n");
         }
 
         func_def
     }
 
+    fn get_header(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].trim_end().to_string()
+    }
+
+    fn get_docstring(&self, statement: Node) -> Option<String> {
+        if statement.kind() != "expression_statement" || statement.named_child_count() != 1 {
+            return None;
+        }
+        let string = statement.named_child(0).filter(|n| n.kind() == "string")?;
+        let text = self.get_node_text(string);
+        // Keep the summary line only, in whichever quote style was used
+        let quote = if text.ends_with("\"\"\"") || text.ends_with("\'\'\'") { &text[text.len() - 3..] } else { &text[text.len() - 1..] };
+        let content = text.trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .trim_start_matches(quote)
+            .trim_end_matches(quote);
+        let summary = content.lines().map(|line| line.trim()).find(|line| !line.is_empty()).unwrap_or("");
+        Some(format!("{}{}{}", quote, summary, quote))
+    }
+
     fn get_node_text(&self, node: Node) -> String {
         self.source_code[node.start_byte()..node.end_byte()].to_string()
     }
 
-    #[allow(dead_code)]
-    fn process_node_func_class(&self, cursor: &mut TreeCursor) -> String {
-        let mut result = String::new();
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
 
         loop {
             let node = cursor.node();
 
-            match node.kind() {
-                "function_definition" | "class_definition" => {
-                    let name = self.get_name(node);
-                    let start_line = node.start_position().row;
-                    let _end_line = node.end_position().row;
-
-                    writeln!(&mut result, "# Code for: {}", self.get_line(start_line)).unwrap();
-                    writeln!(&mut result, "def {name}(...):").unwrap();
-                    writeln!(&mut result, "    pass").unwrap();
-
-                    // Skip the children (implementation details)
-                    cursor.goto_first_child();
-                    while cursor.goto_next_sibling() {}
-                    cursor.goto_parent();
-                },
-                "module" => {
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    // Docstrings rather than comments, and underscores rather than modifiers
+                    let docstring = node.child_by_field_name("body")
+                        .and_then(|body| body.named_child(0))
+                        .and_then(|statement| self.get_docstring(statement));
+                    symbol.doc = docstring.and_then(|docstring| first_sentence(docstring.trim_matches(|c| c == '"' || c == '\'')));
+                    let is_dunder = name.starts_with("__") && name.ends_with("__");
+                    symbol.visibility = Some(if name.starts_with('_') && !is_dunder { "private" } else { "public" }.to_string());
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node(cursor));
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
                         cursor.goto_parent();
                     }
+                    symbols.push(symbol);
                 },
-                _ => {
+                None => {
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node(cursor));
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
                         cursor.goto_parent();
                     }
-                }
+                },
             }
 
             if !cursor.goto_next_sibling() {
@@ -189,7 +268,16 @@ impl PythonSegmenter {
             }
         }
 
-        result
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "function_definition" => "function",
+            "class_definition" => "class",
+            _ => return None,
+        };
+        Some((kind, self.get_name(node).to_string()))
     }
 
     fn get_name(&self, node: Node) -> &str {
@@ -197,8 +285,4 @@ impl PythonSegmenter {
             .and_then(|name_node| name_node.utf8_text(self.source_code.as_bytes()).ok())
             .unwrap_or("unknown")
     }
-
-    fn get_line(&self, line_number: usize) -> &str {
-        self.source_code.lines().nth(line_number).unwrap_or("")
-    }
 }
\ No newline at end of file
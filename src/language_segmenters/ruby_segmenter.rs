@@ -0,0 +1,226 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_ruby::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, qualify_name};
+
+pub struct RubySegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for RubySegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "")
+    }
+}
+
+impl RubySegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Ruby grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Ruby code");
+
+        Box::new(RubySegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "program" => self.process_program(cursor),
+            "module" => self.process_module(cursor),
+            "class" => self.process_class(cursor),
+            "singleton_class" => self.process_singleton_class(cursor),
+            "method" | "singleton_method" => self.process_method(cursor),
+            "call" => self.process_call(cursor),
+            "assignment" => self.process_assignment(cursor),
+            "identifier" => self.process_visibility(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_program(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_body(&self, node: Node) -> String {
+        let mut result = String::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut body_cursor = body.walk();
+            let body_text = self.process_program(&mut body_cursor);
+            result.push_str(&body_text.lines().map(|line| format!("  {}\n", line)).collect::<String>());
+        }
+        result
+    }
+
+    fn process_module(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let module_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "UnnamedModule".to_string());
+
+        let mut module_def = format!("module {}\n", module_name);
+        module_def.push_str(&self.process_body(node));
+        module_def.push_str("end");
+        module_def
+    }
+
+    fn process_class(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let class_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "UnnamedClass".to_string());
+
+        let mut class_def = match node.child_by_field_name("superclass") {
+            Some(superclass) => format!("class {} {}\n", class_name, self.get_node_text(superclass)),
+            None => format!("class {}\n", class_name),
+        };
+        class_def.push_str(&self.process_body(node));
+        class_def.push_str("end");
+        class_def
+    }
+
+    fn process_singleton_class(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let value = node.child_by_field_name("value")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "self".to_string());
+
+        let mut class_def = format!("class << {}\n", value);
+        class_def.push_str(&self.process_body(node));
+        class_def.push_str("end");
+        class_def
+    }
+
+    fn process_method(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        format!("{}; end", self.get_signature(node))
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        let method_name = node.child_by_field_name("name")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "unnamed".to_string());
+        let params = node.child_by_field_name("parameters")
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_default();
+
+        match node.child_by_field_name("object") {
+            Some(object) => format!("def {}.{}{}", self.get_node_text(object), method_name, params),
+            None => format!("def {}{}", method_name, params),
+        }
+    }
+
+    fn process_call(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let block = match node.child_by_field_name("block") {
+            Some(block) => block,
+            // DSL calls such as `has_many` or `include` carry the architecture, keep them verbatim
+            None => return self.get_node_text(node),
+        };
+
+        let header = self.source_code[node.start_byte()..block.start_byte()].trim_end();
+        let block_body = self.process_body(block);
+        if block_body.trim().is_empty() {
+            format!("{} do ... end", header)
+        } else {
+            format!("{} do\n{}end", header, block_body)
+        }
+    }
+
+    fn process_assignment(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let left = match node.child_by_field_name("left") {
+            Some(left) if left.kind() == "constant" => left,
+            _ => return String::new(),
+        };
+
+        let assignment_text = self.get_node_text(node);
+        if assignment_text.contains('\n') {
+            format!("{} = ...", self.get_node_text(left))
+        } else {
+            assignment_text
+        }
+    }
+
+    fn process_visibility(&self, cursor: &mut TreeCursor) -> String {
+        let identifier = self.get_node_text(cursor.node());
+        match identifier.as_str() {
+            "private" | "protected" | "public" | "module_function" => identifier,
+            _ => String::new(),
+        }
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    // Ruby's own notation: `Outer::Inner`, `Klass#instance_method`, `Klass.singleton_method`
+                    let separator = match node.kind() {
+                        "method" => "#",
+                        "singleton_method" => ".",
+                        _ => "::",
+                    };
+                    let qualified_name = qualify_name(scope, &name, separator);
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name);
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_class(cursor, scope));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "class" => "class",
+            "module" => "module",
+            "method" | "singleton_method" => "method",
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+    }
+}
@@ -1,7 +1,6 @@
-use std::fmt::Write;
 use tree_sitter::{Parser, Node, TreeCursor};
 use tree_sitter_rust::language;
-use crate::code_segmenter::CodeSegmenter;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
 
 pub struct RustSegmenter {
     tree: tree_sitter::Tree,
@@ -14,9 +13,9 @@ impl CodeSegmenter for RustSegmenter {
         self.process_node(&mut cursor)
     }
 
-    fn extract_functions_classes(&self) -> String {
+    fn extract_symbols(&self) -> Vec<Symbol> {
         let mut cursor = self.tree.walk();
-        self.process_node_func_struct(&mut cursor)
+        self.process_node_func_struct(&mut cursor, "", false)
     }
 }
 
@@ -40,6 +39,10 @@ impl RustSegmenter {
             "function_item" => self.process_function(cursor),
             "impl_item" => self.process_impl(cursor),
             "mod_item" => self.process_mod(cursor),
+            "enum_item" => self.process_enum(cursor),
+            "trait_item" => self.process_trait(cursor),
+            "const_item" | "static_item" => self.process_const(node),
+            "macro_definition" => self.process_macro(cursor),
             _ => self.get_node_text(node),
         }
     }
@@ -51,7 +54,7 @@ impl RustSegmenter {
                 let node_text = self.process_node(cursor);
                 if !node_text.trim().is_empty() {
                     result.push_str(&node_text);
-                    result.push_str("\n");
+                    result.push('\n');
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -64,140 +67,213 @@ impl RustSegmenter {
 
     fn process_struct(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let struct_text = self.get_node_text(node);
-        
-        // Split the struct text into lines
-        let lines: Vec<&str> = struct_text.lines().collect();
-        
-        let mut result = String::new();
-        let mut in_struct_body = false;
-        let mut brace_count = 0;
-
-        for line in lines {
-            // Always include lines with attributes or the struct definition
-            if line.trim().starts_with('#') || line.contains("struct") {
-                result.push_str(line);
-                result.push('\n');
-                if line.contains('{') {
-                    in_struct_body = true;
-                    brace_count += 1;
-                }
-                continue;
-            }
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            // Unit structs
+            None => return format!("{}\n", self.get_node_text(node)),
+        };
 
-            if in_struct_body {
-                brace_count += line.matches('{').count() as i32;
-                brace_count -= line.matches('}').count() as i32;
-
-                // Check if this line starts a method
-                if line.contains("fn ") {
-                    // For methods, we only include the signature
-                    let method_signature = line.split('{').next().unwrap_or(line).trim();
-                    result.push_str("    ");
-                    result.push_str(method_signature);
-                    result.push_str(" { ... }\n");
-                    
-                    // Skip the rest of the method body
-                    while brace_count > 1 {
-                        brace_count -= 1;
-                    }
-                } else if !line.trim().starts_with('}') {
-                    // Include all other lines in the struct body (fields, etc.)
-                    result.push_str(line);
-                    result.push('\n');
+        match body.kind() {
+            "field_declaration_list" => {
+                // Visibility, generics and where clause
+                let mut struct_def = format!("{} {{\n", self.get_header(node));
+                for child in body.named_children(&mut body.walk()) {
+                    let field = match child.kind() {
+                        "field_declaration" => format!("{},", self.get_node_text(child)),
+                        "attribute_item" => self.get_node_text(child),
+                        "line_comment" | "block_comment" if self.is_doc_comment(child) => self.get_node_text(child),
+                        _ => continue,
+                    };
+                    struct_def.push_str(&field.lines().map(|line| format!("    {}\n", line.trim())).collect::<String>());
                 }
+                struct_def.push_str("}\n");
+                struct_def
+            },
+            _ => {
+                // Tuple structs, the where clause comes after the fields here
+                let fields = self.get_ordered_fields(body);
+                format!(
+                    "{}({}){}\n",
+                    self.get_header(node),
+                    fields.join(", "),
+                    &self.source_code[body.end_byte()..node.end_byte()],
+                )
+            },
+        }
+    }
 
-                if brace_count == 0 {
-                    in_struct_body = false;
-                    result.push_str("}\n");
-                }
+    fn get_ordered_fields(&self, body: Node) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field: Vec<String> = Vec::new();
+        for child in body.children(&mut body.walk()) {
+            match child.kind() {
+                "(" | ")" | "," => {
+                    if !field.is_empty() {
+                        fields.push(field.join(" "));
+                        field.clear();
+                    }
+                },
+                "line_comment" | "block_comment" => {},
+                _ => field.push(self.get_node_text(child)),
             }
         }
-
-        result
+        fields
     }
     
     fn process_function(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let fn_text = self.get_node_text(node);
-        
-        // Extract the function signature (everything before the first '{')
-        let signature = fn_text.split('{').next().unwrap_or(&fn_text).trim();
-        
-        format!("{};\n", signature)
+        // Everything up to the body, so generics, return types and where clauses survive
+        format!("{};\n", self.get_header(node))
     }
 
     fn process_impl(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let type_name = node.child_by_field_name("type")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnknownType".to_string());
-        
-        let mut impl_block = format!("impl {} {{\n", type_name);
+        // `impl<T> Trait for Type<T> where ...`
+        let mut impl_block = format!("{} {{\n", self.get_header(node));
         
         if let Some(body) = node.child_by_field_name("body") {
-            for child in body.children(&mut body.walk()) {
-                match child.kind() {
-                    "function_item" => {
-                        let mut child_cursor = child.walk();
-                        let method_def = self.process_function(&mut child_cursor);
-                        impl_block.push_str(&method_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
-                    },
-                    _ => {}
-                }
-            }
+            impl_block.push_str(&self.process_members(body));
         }
         
         impl_block.push_str("}\n");
         impl_block
     }
 
-    fn process_mod(&self, cursor: &mut TreeCursor) -> String {
+    fn process_trait(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let mut trait_def = format!("{} {{\n", self.get_header(node));
+
+        if let Some(body) = node.child_by_field_name("body") {
+            trait_def.push_str(&self.process_members(body));
+        }
+
+        trait_def.push_str("}\n");
+        trait_def
+    }
+
+    fn process_members(&self, body: Node) -> String {
+        let mut members = String::new();
+        for child in body.named_children(&mut body.walk()) {
+            let member = match child.kind() {
+                "function_item" => {
+                    let mut child_cursor = child.walk();
+                    self.process_function(&mut child_cursor)
+                },
+                "function_signature_item" | "associated_type" | "type_item" => self.get_node_text(child),
+                "const_item" => self.process_const(child),
+                _ => continue,
+            };
+            members.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+        members
+    }
+
+    fn process_enum(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let mod_name = node.child_by_field_name("name")
+        let mut enum_def = format!("{} {{\n", self.get_header(node));
+
+        if let Some(body) = node.child_by_field_name("body") {
+            for child in body.named_children(&mut body.walk()) {
+                let variant = match child.kind() {
+                    "enum_variant" => format!("{},", self.get_node_text(child)),
+                    "attribute_item" => self.get_node_text(child),
+                    "line_comment" | "block_comment" if self.is_doc_comment(child) => self.get_node_text(child),
+                    _ => continue,
+                };
+                enum_def.push_str(&variant.lines().map(|line| format!("    {}\n", line.trim())).collect::<String>());
+            }
+        }
+
+        enum_def.push_str("}\n");
+        enum_def
+    }
+
+    fn process_const(&self, node: Node) -> String {
+        let text = self.get_node_text(node);
+        match node.child_by_field_name("value") {
+            // Large initialisers (tables, lazy statics) only keep their type
+            Some(value) if text.contains('\n') => {
+                format!("{}= ...;", &self.source_code[node.start_byte()..value.start_byte()])
+            },
+            _ => text,
+        }
+    }
+
+    fn process_macro(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let macro_name = node.child_by_field_name("name")
             .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "unnamed_mod".to_string());
-        
-        format!("mod {};\n", mod_name)
+            .unwrap_or_default();
+
+        let mut macro_def = format!("macro_rules! {} {{\n", macro_name);
+        for rule in node.named_children(&mut node.walk()) {
+            if let Some(pattern) = rule.child_by_field_name("left") {
+                macro_def.push_str(&format!("    {} => {{ ... }};\n", self.get_node_text(pattern)));
+            }
+        }
+
+        macro_def.push_str("}\n");
+        macro_def
     }
 
+    fn process_mod(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        // Inline modules hold the same items as a file
+        let mut mod_block = format!("{} {{\n", self.get_header(node));
+        for child in body.named_children(&mut body.walk()) {
+            let mut child_cursor = child.walk();
+            let item = self.process_node(&mut child_cursor);
+            mod_block.push_str(&item.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+
+        mod_block.push_str("}\n");
+        mod_block
+    }
+
+    fn get_header(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source_code[node.start_byte()..end].trim_end().to_string()
+    }
+
+    fn is_doc_comment(&self, node: Node) -> bool {
+        let text = self.get_node_text(node);
+        text.starts_with("///") || text.starts_with("/**")
+    }
 
     fn get_node_text(&self, node: Node) -> String {
         self.source_code[node.start_byte()..node.end_byte()].to_string()
     }
 
-    #[allow(dead_code)]
-    fn process_node_func_struct(&self, cursor: &mut TreeCursor) -> String {
-        let mut result = String::new();
+    fn process_node_func_struct(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
 
         loop {
             let node = cursor.node();
 
-            match node.kind() {
-                "function_item" | "struct_item" | "impl_item" | "mod_item" => {
-                    let start_line = node.start_position().row;
-                    writeln!(&mut result, "// Code for: {}", self.get_line(start_line)).unwrap();
-                    
-                    match node.kind() {
-                        "function_item" => result.push_str(&self.process_function(cursor)),
-                        "struct_item" => result.push_str(&self.process_struct(cursor)),
-                        "impl_item" => result.push_str(&self.process_impl(cursor)),
-                        "mod_item" => result.push_str(&self.process_mod(cursor)),
-                        _ => unreachable!(),
-                    }
-                },
-                "source_file" => {
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, "::");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_struct(cursor));
+                        symbol.children = self.process_node_func_struct(cursor, &qualified_name, is_type_kind(kind));
                         cursor.goto_parent();
                     }
+                    symbols.push(symbol);
                 },
-                _ => {
+                None => {
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_struct(cursor));
+                        symbols.extend(self.process_node_func_struct(cursor, scope, in_type));
                         cursor.goto_parent();
                     }
-                }
+                },
             }
 
             if !cursor.goto_next_sibling() {
@@ -205,10 +281,27 @@ impl RustSegmenter {
             }
         }
 
-        result
+        symbols
     }
 
-    fn get_line(&self, line_number: usize) -> &str {
-        self.source_code.lines().nth(line_number).unwrap_or("")
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "function_item" | "function_signature_item" => "function",
+            "struct_item" => "struct",
+            "union_item" => "union",
+            "enum_item" => "enum",
+            "trait_item" => "trait",
+            "type_item" => "type",
+            "mod_item" => "module",
+            "macro_definition" => "macro",
+            "impl_item" => {
+                // Members are listed under the implementing type, without its generics
+                let type_name = self.get_node_text(node.child_by_field_name("type")?);
+                let type_name = type_name.split('<').next().unwrap_or(&type_name).trim();
+                return Some(("impl", type_name.to_string()));
+            },
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
     }
 }
\ No newline at end of file
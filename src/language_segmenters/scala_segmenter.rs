@@ -0,0 +1,231 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_scala::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct ScalaSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for ScalaSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
+    }
+}
+
+impl ScalaSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Scala grammar");
+        let tree = parser.parse(blank_given_braces(&code), None).expect("Failed to parse Scala code");
+
+        Box::new(ScalaSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            // Keep whatever the parser could make sense of
+            "compilation_unit" | "ERROR" => self.process_children(cursor),
+            "package_clause" => self.process_package(cursor),
+            "import_declaration" | "export_declaration" => self.get_node_text(node),
+            "class_definition" | "object_definition" | "trait_definition" | "enum_definition" => self.process_template(cursor),
+            "given_definition" if node.child_by_field_name("body").is_some_and(|body| body.kind() == "with_template_body") => self.process_template(cursor),
+            "function_definition" | "given_definition" | "val_definition" | "var_definition" => self.process_definition(cursor),
+            "function_declaration" | "val_declaration" | "var_declaration" | "type_definition" => self.get_node_text(node),
+            "enum_case_definitions" => self.get_node_text(node),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_package(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        let header = self.source_code[node.start_byte()..body.start_byte()].trim_end();
+        let mut body_cursor = body.walk();
+        let body_text = self.process_children(&mut body_cursor);
+
+        let mut package_def = format!("{} {{\n", header);
+        package_def.push_str(&body_text.lines().map(|line| format!("  {}\n", line)).collect::<String>());
+        package_def.push('}');
+        package_def
+    }
+
+    fn process_template(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        // Modifiers, type parameters, constructor parameters and extends clause. A
+        // `given ... with {` body starts after its blanked out brace
+        let header = self.source_code[node.start_byte()..body.start_byte()].trim_end().trim_end_matches('{').trim_end();
+        let mut body_cursor = body.walk();
+        let body_text = self.process_children(&mut body_cursor);
+
+        let mut template_def = format!("{} {{\n", header);
+        template_def.push_str(&body_text.lines().map(|line| format!("  {}\n", line)).collect::<String>());
+        template_def.push('}');
+        template_def
+    }
+
+    fn process_definition(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        self.get_signature(node)
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        // Everything before `= body`, so return types and implicit/using clauses survive
+        let end = node.child_by_field_name("body")
+            .or_else(|| node.child_by_field_name("value"))
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let signature = self.source_code[node.start_byte()..end].trim_end();
+        signature.trim_end_matches('=').trim_end().to_string()
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    // Indented bodies run up to the next token, leave out the blank lines before it
+                    let end = self.source_code[..node.end_byte()].trim_end().len().max(node.start_byte());
+                    symbol.byte_range.end = end;
+                    symbol.end_line = self.source_code[..end].matches('\n').count() + 1;
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "class_definition" => "class",
+            "object_definition" => "object",
+            "trait_definition" => "trait",
+            "enum_definition" => "enum",
+            "function_definition" | "function_declaration" => "function",
+            "given_definition" => {
+                // Anonymous givens are named after their type, `given_Ordering_String`
+                let name = match node.child_by_field_name("name") {
+                    Some(name) => self.get_node_text(name),
+                    None => {
+                        let given_type = self.get_node_text(node.child_by_field_name("return_type")?);
+                        let parts: Vec<&str> = given_type.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|part| !part.is_empty()).collect();
+                        format!("given_{}", parts.join("_"))
+                    },
+                };
+                return Some(("given", name));
+            },
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+    }
+}
+
+/// The grammar only knows the indented form of `given ... with`, so the braces of
+/// `given ... with { ... }` are blanked out before parsing. Byte offsets don't
+/// change, so the nodes still slice the original source.
+fn blank_given_braces(code: &str) -> String {
+    let bytes = code.as_bytes();
+    let mut blanked = bytes.to_vec();
+    // One entry per open brace, whether it opens a given's body
+    let mut braces: Vec<bool> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = code[i..].find('\n').map_or(bytes.len(), |end| i + end);
+            },
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = code[i + 2..].find("*/").map_or(bytes.len(), |end| i + end + 3);
+            },
+            b'"' if code[i..].starts_with("\"\"\"") => {
+                i = code[i + 3..].find("\"\"\"").map_or(bytes.len(), |end| i + end + 5);
+            },
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && !matches!(bytes[i], b'"' | b'\n') {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            },
+            b'\'' if bytes.get(i + 2) == Some(&b'\'') => i += 2,
+            b'{' => {
+                let line_start = code[..i].rfind('\n').map_or(0, |start| start + 1);
+                let before = code[line_start..i].trim_end();
+                let is_given = before.ends_with("with") && before.split_whitespace().any(|word| word == "given");
+                if is_given {
+                    blanked[i] = b' ';
+                }
+                braces.push(is_given);
+            },
+            b'}' => {
+                let closes_given = braces.pop().unwrap_or(false);
+                blanked[i] = if closes_given { b' ' } else { b'}' };
+            },
+            _ => {},
+        }
+        i += 1;
+    }
+    // Only ASCII braces were replaced
+    String::from_utf8(blanked).unwrap()
+}
@@ -0,0 +1,181 @@
+use tree_sitter::{Parser, Node, TreeCursor};
+use tree_sitter_swift::language;
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
+
+pub struct SwiftSegmenter {
+    tree: tree_sitter::Tree,
+    source_code: String,
+}
+
+impl CodeSegmenter for SwiftSegmenter {
+    fn simplify_code(&self) -> String {
+        let mut cursor = self.tree.walk();
+        self.process_node(&mut cursor)
+    }
+
+    fn extract_symbols(&self) -> Vec<Symbol> {
+        let mut cursor = self.tree.walk();
+        self.process_node_func_class(&mut cursor, "", false)
+    }
+}
+
+impl SwiftSegmenter {
+    pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        let mut parser = Parser::new();
+        parser.set_language(language()).expect("Error loading Swift grammar");
+        let tree = parser.parse(&code, None).expect("Failed to parse Swift code");
+
+        Box::new(SwiftSegmenter {
+            tree,
+            source_code: code,
+        })
+    }
+
+    fn process_node(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        match node.kind() {
+            "source_file" => self.process_children(cursor),
+            "import_declaration" | "typealias_declaration" | "enum_entry" => self.get_node_text(node),
+            "protocol_declaration" => self.get_node_text(node),
+            // Covers class, struct, enum, extension and actor declarations
+            "class_declaration" => self.process_class(cursor),
+            "property_declaration" => self.process_property(cursor),
+            "function_declaration" | "init_declaration" | "deinit_declaration" | "subscript_declaration" => self.process_function(cursor),
+            _ => String::new(),
+        }
+    }
+
+    fn process_children(&self, cursor: &mut TreeCursor) -> String {
+        let mut result = String::new();
+        if cursor.goto_first_child() {
+            loop {
+                let node_text = self.process_node(cursor);
+                if !node_text.trim().is_empty() {
+                    result.push_str(&node_text);
+                    result.push('\n');
+                }
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+        result.trim_end().to_string()
+    }
+
+    fn process_class(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        // Attributes, modifiers, declaration kind, name and conformances
+        let header = self.normalize_lines(&self.source_code[node.start_byte()..body.start_byte()]);
+        let mut body_cursor = body.walk();
+        let body_text = self.process_children(&mut body_cursor);
+
+        let mut class_def = format!("{} {{\n", header);
+        class_def.push_str(&body_text.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        class_def.push('}');
+        class_def
+    }
+
+    fn process_property(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let computed = node.child_by_field_name("computed_value");
+        let end = node.child_by_field_name("value")
+            .or(computed)
+            .map(|value| value.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        let declaration = self.normalize_lines(&self.source_code[node.start_byte()..end]);
+        let declaration = declaration.trim_end_matches('=').trim_end();
+
+        match computed {
+            Some(computed) => {
+                let has_setter = computed.named_children(&mut computed.walk())
+                    .any(|accessor| accessor.kind() == "computed_setter");
+                if has_setter {
+                    format!("{} {{ get set }}", declaration)
+                } else {
+                    format!("{} {{ get }}", declaration)
+                }
+            },
+            None => declaration.to_string(),
+        }
+    }
+
+    fn process_function(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        self.get_signature(node)
+    }
+
+    fn get_signature(&self, node: Node) -> String {
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.normalize_lines(&self.source_code[node.start_byte()..end])
+    }
+
+    fn normalize_lines(&self, text: &str) -> String {
+        // Attributes on their own lines keep the member's original indentation otherwise
+        text.trim().lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
+    }
+
+    fn get_node_text(&self, node: Node) -> String {
+        self.source_code[node.start_byte()..node.end_byte()].to_string()
+    }
+
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
+
+        loop {
+            let node = cursor.node();
+
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
+                    if cursor.goto_first_child() {
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
+                        cursor.goto_parent();
+                    }
+                    symbols.push(symbol);
+                },
+                None => {
+                    if cursor.goto_first_child() {
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
+                        cursor.goto_parent();
+                    }
+                },
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+
+        symbols
+    }
+
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        match node.kind() {
+            "function_declaration" => Some(("function", self.get_node_text(node.child_by_field_name("name")?))),
+            "init_declaration" => Some(("function", "init".to_string())),
+            "protocol_declaration" => Some(("protocol", self.get_node_text(node.child_by_field_name("name")?))),
+            "class_declaration" => {
+                // Structs, enums, actors and extensions share the class node
+                let kind = match node.child_by_field_name("declaration_kind").map(|n| self.get_node_text(n)).as_deref() {
+                    Some("struct") => "struct",
+                    Some("enum") => "enum",
+                    Some("actor") => "actor",
+                    Some("extension") => "extension",
+                    _ => "class",
+                };
+                Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+            },
+            _ => None,
+        }
+    }
+}
@@ -1,33 +1,52 @@
-use tree_sitter::{Parser, Node, TreeCursor};
-use tree_sitter_typescript::language_typescript as language;
-use crate::code_segmenter::CodeSegmenter;
+use tree_sitter::{Language, Parser, Node, TreeCursor};
+use tree_sitter_typescript::{language_tsx, language_typescript};
+use crate::code_segmenter::{CodeSegmenter, Symbol, is_type_kind, qualify_name};
 
 pub struct TypeScriptSegmenter {
     tree: tree_sitter::Tree,
     source_code: String,
+    is_declaration_file: bool,
 }
 
 impl CodeSegmenter for TypeScriptSegmenter {
     fn simplify_code(&self) -> String {
+        // `.d.ts` files are already nothing but signatures
+        if self.is_declaration_file {
+            return self.source_code.trim_end().to_string();
+        }
         let mut cursor = self.tree.walk();
         self.process_node(&mut cursor)
     }
 
-    fn extract_functions_classes(&self) -> String {
+    fn extract_symbols(&self) -> Vec<Symbol> {
         let mut cursor = self.tree.walk();
-        self.process_node_func_class(&mut cursor)
+        self.process_node_func_class(&mut cursor, "", false)
     }
 }
 
 impl TypeScriptSegmenter {
     pub fn new(code: String) -> Box<dyn CodeSegmenter> {
+        Self::with_language(code, language_typescript(), false)
+    }
+
+    /// `.tsx` and `.jsx` files need the grammar variant that understands JSX.
+    pub fn new_tsx(code: String) -> Box<dyn CodeSegmenter> {
+        Self::with_language(code, language_tsx(), false)
+    }
+
+    pub fn new_declaration(code: String) -> Box<dyn CodeSegmenter> {
+        Self::with_language(code, language_typescript(), true)
+    }
+
+    fn with_language(code: String, language: Language, is_declaration_file: bool) -> Box<dyn CodeSegmenter> {
         let mut parser = Parser::new();
-        parser.set_language(language()).expect("Error loading TypeScript grammar");
+        parser.set_language(language).expect("Error loading TypeScript grammar");
         let tree = parser.parse(&code, None).expect("Failed to parse TypeScript code");
 
         Box::new(TypeScriptSegmenter {
             tree,
             source_code: code,
+            is_declaration_file,
         })
     }
 
@@ -35,11 +54,17 @@ impl TypeScriptSegmenter {
         let node = cursor.node();
         match node.kind() {
             "program" => self.process_program(cursor),
-            "class_declaration" => self.process_class(cursor),
+            "class_declaration" | "abstract_class_declaration" => self.process_class(cursor),
             "function_declaration" => self.process_function(cursor),
             "method_definition" => self.process_method(cursor),
             "export_statement" => self.process_export(cursor),
             "variable_declaration" => self.process_variable_declaration(cursor),
+            "lexical_declaration" => self.process_lexical_declaration(cursor),
+            "interface_declaration" => self.process_interface(cursor),
+            "enum_declaration" => self.process_enum(cursor),
+            "internal_module" | "module" => self.process_module(cursor),
+            "ambient_declaration" => self.process_ambient(cursor),
+            "expression_statement" => self.process_expression_statement(cursor),
             "comment" => String::new(), // Ignore comments
             _ => self.get_node_text(node),
         }
@@ -47,60 +72,91 @@ impl TypeScriptSegmenter {
 
     fn process_interface(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let interface_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnnamedInterface".to_string());
-    
-        let mut interface_def = format!("interface {} {{\n", interface_name);
-    
-        if let Some(body) = node.child_by_field_name("body") {
-            for child in body.children(&mut body.walk()) {
-                match child.kind() {
-                    "formal_parameters" => {
-                        let method_def = self.get_node_text(child);
-                        interface_def.push_str(&format!("    {};\n", method_def));
-                    },
-                    "property_definition" => {
-                        let property_def = self.get_node_text(child);
-                        interface_def.push_str(&format!("    {};\n", property_def));
-                    },
-                    _ => {}
-                }
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        // Keeps type parameters and the extends clause
+        let mut interface_def = format!("{} {{\n", self.get_header(node, body));
+        for child in body.named_children(&mut body.walk()) {
+            if child.kind() != "comment" {
+                interface_def.push_str(&format!("    {};\n", self.normalize_lines(&self.get_node_text(child))));
             }
         }
-    
-        interface_def.push_str("}\n");
+
+        interface_def.push('}');
         interface_def
     }
-    
-    fn process_abstract_class(&self, cursor: &mut TreeCursor) -> String {
+
+    fn process_enum(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let class_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnnamedAbstractClass".to_string());
-    
-        let mut class_def = format!("abstract class {} {{\n", class_name);
-    
-        if let Some(body) = node.child_by_field_name("body") {
-            for child in body.children(&mut body.walk()) {
-                match child.kind() {
-                    "method_definition" => {
-                        let mut child_cursor = child.walk();
-                        let method_def = self.process_method(&mut child_cursor);
-                        class_def.push_str(&method_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
-                    },
-                    "public_field_definition" => {
-                        let field_def = self.get_node_text(child);
-                        class_def.push_str(&format!("    {};\n", field_def));
-                    },
-                    _ => {}
-                }
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        let mut enum_def = format!("{} {{\n", self.get_header(node, body));
+        for member in body.named_children(&mut body.walk()) {
+            if member.kind() != "comment" {
+                enum_def.push_str(&format!("    {},\n", self.get_node_text(member)));
             }
         }
-    
-        class_def.push_str("}\n");
-        class_def
+
+        enum_def.push('}');
+        enum_def
+    }
+
+    fn process_module(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        format!("{} {}", self.get_header(node, body), self.process_block(body))
+    }
+
+    fn process_block(&self, block: Node) -> String {
+        // Namespace and `declare` bodies hold the same statements as a program
+        let mut members = String::new();
+        for child in block.named_children(&mut block.walk()) {
+            let mut child_cursor = child.walk();
+            let member = self.process_node(&mut child_cursor);
+            members.push_str(&member.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+        }
+        format!("{{\n{}}}", members)
+    }
+
+    fn process_ambient(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let declaration = match node.named_child(0) {
+            Some(declaration) => declaration,
+            None => return self.get_node_text(node),
+        };
+
+        // `declare global { ... }` has a bare block, the rest wrap a regular declaration
+        if declaration.kind() == "statement_block" {
+            format!("{} {}", self.get_header(node, declaration), self.process_block(declaration))
+        } else {
+            let prefix = &self.source_code[node.start_byte()..declaration.start_byte()];
+            let mut declaration_cursor = declaration.walk();
+            format!("{}{}", prefix, self.process_node(&mut declaration_cursor))
+        }
     }
+
+    fn process_expression_statement(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        // A non-exported `namespace Foo { }` parses as an expression
+        match node.named_child(0) {
+            Some(module) if module.kind() == "internal_module" => {
+                let mut module_cursor = module.walk();
+                self.process_module(&mut module_cursor)
+            },
+            _ => self.get_node_text(node),
+        }
+    }
+
     fn process_program(&self, cursor: &mut TreeCursor) -> String {
         let mut result = String::new();
         if cursor.goto_first_child() {
@@ -108,7 +164,7 @@ impl TypeScriptSegmenter {
                 let node_text = self.process_node(cursor);
                 if !node_text.trim().is_empty() {
                     result.push_str(&node_text);
-                    result.push_str("\n");
+                    result.push('\n');
                 }
                 if !cursor.goto_next_sibling() {
                     break;
@@ -121,98 +177,184 @@ impl TypeScriptSegmenter {
 
     fn process_export(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        if let Some(declaration) = node.child(1) {
-            match declaration.kind() {
-                "function_declaration" => {
-                    let mut declaration_cursor = declaration.walk();
-                    format!("export {}", self.process_function(&mut declaration_cursor))
-                },
-                "class_declaration" => {
-                    let mut declaration_cursor = declaration.walk();
-                    format!("export {}", self.process_class(&mut declaration_cursor))
-                },
-                "interface_declaration" => {
-                    let mut declaration_cursor = declaration.walk();
-                    format!("export {}", self.process_interface(&mut declaration_cursor))
-                },
-                "abstract_class_declaration" => {
-                    let mut declaration_cursor = declaration.walk();
-                    format!("export {}", self.process_abstract_class(&mut declaration_cursor))
-                },
-                "default_keyword" => {
-                    if let Some(class_decl) = node.child(2) {
-                        let mut class_cursor = class_decl.walk();
-                        format!("export default {}", self.process_class(&mut class_cursor))
-                    } else {
-                        String::new()
-                    }
-                },
-                _ => String::new(),
-            }
-        } else {
-            String::new()
-        }
+        let declaration = match node.child_by_field_name("declaration") {
+            Some(declaration) => declaration,
+            // `export { a, b }` and `export * from "./b"`
+            None => return self.get_node_text(node),
+        };
+        // `export ` or `export default `
+        let prefix = &self.source_code[node.start_byte()..declaration.start_byte()];
+        let mut declaration_cursor = declaration.walk();
+        format!("{}{}", prefix, self.process_node(&mut declaration_cursor))
     }
+
     fn process_variable_declaration(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
+        // `const`, `let` or `var`
+        let kind = node.child(0)
+            .map(|n| self.get_node_text(n))
+            .unwrap_or_else(|| "const".to_string());
+
         let mut result = String::new();
+        for declarator in node.named_children(&mut node.walk()) {
+            if declarator.kind() != "variable_declarator" {
+                continue;
+            }
+            let name = match declarator.child_by_field_name("name") {
+                Some(name) => self.get_node_text(name),
+                None => continue,
+            };
+            let type_annotation = declarator.child_by_field_name("type")
+                .map(|n| self.get_node_text(n))
+                .unwrap_or_default();
 
-        if let Some(declarators) = node.child_by_field_name("declarators") {
-            for declarator in declarators.children(&mut declarators.walk()) {
-                if let Some(name) = declarator.child_by_field_name("name") {
-                    let var_name = self.get_node_text(name);
-                    if let Some(init) = declarator.child_by_field_name("init") {
-                        let var_init = self.get_node_text(init);
-                        result.push_str(&format!("const {} = {};\n", var_name, var_init));
+            let value = match declarator.child_by_field_name("value") {
+                // Arrow functions and function expressions become signatures
+                Some(value) if matches!(value.kind(), "arrow_function" | "function" | "function_expression") => {
+                    format!(" = {} {{ }}", self.get_signature(value))
+                },
+                Some(value) => {
+                    let value_text = self.get_node_text(value);
+                    if value_text.contains('\n') {
+                        " = ...".to_string()
                     } else {
-                        result.push_str(&format!("const {};\n", var_name));
+                        format!(" = {}", value_text)
                     }
-                }
-            }
+                },
+                None => String::new(),
+            };
+            result.push_str(&format!("{} {}{}{};\n", kind, name, type_annotation, value));
         }
 
-        result
+        result.trim_end().to_string()
     }
 
     fn process_class(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let class_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "UnnamedClass".to_string());
-        
-        let mut class_def = format!("class {} {{\n", class_name);
+        let body = match node.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(node),
+        };
+
+        // Decorators, `abstract`, type parameters, extends and implements
+        let mut class_def = format!("{} {{\n", self.get_header(node, body));
         
-        if let Some(body) = node.child_by_field_name("body") {
-            for child in body.children(&mut body.walk()) {
-                match child.kind() {
-                    "method_definition" => {
-                        let mut child_cursor = child.walk();
-                        let method_def = self.process_method(&mut child_cursor);
-                        class_def.push_str(&method_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
-                    },
-                    "public_field_definition" => {
-                        let field_def = self.get_node_text(child);
-                        class_def.push_str(&format!("    {}\n", field_def));
-                    },
-                    _ => {}
-                }
+        for child in body.named_children(&mut body.walk()) {
+            match child.kind() {
+                "method_definition" => {
+                    let mut child_cursor = child.walk();
+                    let method_def = self.process_method(&mut child_cursor);
+                    class_def.push_str(&method_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+                },
+                "public_field_definition" | "abstract_method_signature" | "method_signature" | "index_signature" => {
+                    let member_def = format!("{};", self.normalize_lines(&self.get_node_text(child)));
+                    class_def.push_str(&member_def.lines().map(|line| format!("    {}\n", line)).collect::<String>());
+                },
+                _ => {}
             }
         }
         
-        class_def.push_str("}\n");
+        class_def.push('}');
         class_def
     }
 
     fn process_function(&self, cursor: &mut TreeCursor) -> String {
         let node = cursor.node();
-        let func_name = node.child_by_field_name("name")
-            .map(|n| self.get_node_text(n))
-            .unwrap_or_else(|| "unnamed".to_string());
-        let params = node.child_by_field_name("parameters")
-            .map(|p| self.get_node_text(p))
-            .unwrap_or_else(|| "()".to_string());
-        
-        format!("function {}{}{{ }}\n", func_name, params)
+        if self.is_component(node, node) {
+            return self.process_component(node, node);
+        }
+
+        format!("{} {{ }}", self.get_signature(node))
+    }
+
+    fn process_lexical_declaration(&self, cursor: &mut TreeCursor) -> String {
+        let node = cursor.node();
+        let component = node.named_children(&mut node.walk())
+            .find(|child| child.kind() == "variable_declarator")
+            .and_then(|declarator| self.get_declared_function(declarator))
+            .filter(|function| self.is_component(node, *function));
+
+        match component {
+            Some(function) => self.process_component(node, function),
+            None => self.process_variable_declaration(cursor),
+        }
+    }
+
+    fn get_declared_function<'a>(&self, declarator: Node<'a>) -> Option<Node<'a>> {
+        let value = declarator.child_by_field_name("value")?;
+        match value.kind() {
+            "arrow_function" | "function" | "function_expression" => Some(value),
+            // `React.memo(...)` and `forwardRef(...)` wrap the component itself
+            "call_expression" => value.child_by_field_name("arguments")?
+                .named_children(&mut value.walk())
+                .find(|argument| matches!(argument.kind(), "arrow_function" | "function" | "function_expression")),
+            _ => None,
+        }
+    }
+
+    fn is_component(&self, declaration: Node, function: Node) -> bool {
+        // Components are capitalised by convention and render JSX
+        let name = match declaration.kind() {
+            "lexical_declaration" => declaration.named_child(0).and_then(|declarator| declarator.child_by_field_name("name")),
+            _ => declaration.child_by_field_name("name"),
+        };
+        let is_capitalised = name
+            .and_then(|n| self.get_node_text(n).chars().next())
+            .is_some_and(|c| c.is_ascii_uppercase());
+        is_capitalised && function.child_by_field_name("body").is_some_and(|body| self.contains_jsx(body))
+    }
+
+    fn contains_jsx(&self, node: Node) -> bool {
+        node.kind().starts_with("jsx_")
+            || node.named_children(&mut node.walk()).any(|child| self.contains_jsx(child))
+    }
+
+    fn process_component(&self, declaration: Node, function: Node) -> String {
+        let body = match function.child_by_field_name("body") {
+            Some(body) => body,
+            None => return self.get_node_text(declaration),
+        };
+
+        // Everything around the body, e.g. `const Button: React.FC<Props> = ({ label }) =>` and `);`
+        let header = self.source_code[declaration.start_byte()..body.start_byte()].trim_end();
+        let trailer = &self.source_code[body.end_byte()..declaration.end_byte()];
+
+        let mut component_def = format!("{} {{\n", header);
+        let hooks = self.get_hooks(body);
+        if !hooks.is_empty() {
+            component_def.push_str(&format!("    // hooks: {}\n", hooks.join(", ")));
+        }
+        component_def.push_str("    return <... />;\n");
+        component_def.push('}');
+        component_def.push_str(trailer);
+        component_def
+    }
+
+    fn get_hooks(&self, node: Node) -> Vec<String> {
+        let mut hooks: Vec<String> = Vec::new();
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            if current.kind() == "call_expression" {
+                // `useState(...)` or `React.useState(...)`
+                let hook = current.child_by_field_name("function")
+                    .map(|function| function.child_by_field_name("property").unwrap_or(function))
+                    .map(|name| self.get_node_text(name))
+                    .filter(|name| self.is_hook_name(name));
+                if let Some(hook) = hook {
+                    if !hooks.contains(&hook) {
+                        hooks.push(hook);
+                    }
+                }
+            }
+            // Reversed so hooks come out in source order
+            let children: Vec<Node> = current.named_children(&mut current.walk()).collect();
+            stack.extend(children.into_iter().rev());
+        }
+        hooks
+    }
+
+    fn is_hook_name(&self, name: &str) -> bool {
+        name == "use" || name.strip_prefix("use").and_then(|rest| rest.chars().next()).is_some_and(|c| c.is_ascii_uppercase())
     }
 
     fn process_method(&self, cursor: &mut TreeCursor) -> String {
@@ -220,52 +362,59 @@ impl TypeScriptSegmenter {
         let method_name = node.child_by_field_name("name")
             .map(|n| self.get_node_text(n))
             .unwrap_or_else(|| "unnamed".to_string());
-        let params = node.child_by_field_name("parameters")
-            .map(|p| self.get_node_text(p))
-            .unwrap_or_else(|| "()".to_string());
         
         if method_name == "constructor" {
             // Keep the entire constructor method intact
-            if let Some(body) = node.child_by_field_name("body") {
-                let body_text = self.get_node_text(body);
-                format!("{}{}{} {{ }}", method_name, params, body_text)
-            } else {
-                format!("{}{} {{ }}", method_name, params)
-            }
+            self.normalize_lines(&self.get_node_text(node))
         } else {
-            format!("{}{} {{ }}", method_name, params)
+            format!("{} {{ }}", self.get_signature(node))
         }
     }
 
+    fn get_signature(&self, node: Node) -> String {
+        // Modifiers, type parameters, parameters and return type
+        let end = node.child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.normalize_lines(&self.source_code[node.start_byte()..end])
+    }
+
+    fn get_header(&self, node: Node, body: Node) -> String {
+        self.normalize_lines(&self.source_code[node.start_byte()..body.start_byte()])
+    }
+
+    fn normalize_lines(&self, text: &str) -> String {
+        // Decorators on their own lines keep the member's original indentation otherwise
+        text.trim().lines().map(|line| line.trim()).collect::<Vec<&str>>().join("\n")
+    }
+
     fn get_node_text(&self, node: Node) -> String {
         self.source_code[node.start_byte()..node.end_byte()].to_string()
     }
 
-    #[allow(dead_code)]
-    fn process_node_func_class(&self, cursor: &mut TreeCursor) -> String {
-        let mut result = String::new();
+    fn process_node_func_class(&self, cursor: &mut TreeCursor, scope: &str, in_type: bool) -> Vec<Symbol> {
+        let mut symbols = Vec::new();
 
         loop {
             let node = cursor.node();
 
-            match node.kind() {
-                "function_declaration" | "class_declaration" => {
-                    // let name = self.get_name(node);
-                    let node_text = self.process_node(cursor);
-                    result.push_str(&node_text);
-                },
-                "program" => {
+            match self.get_symbol(node) {
+                Some((kind, name)) => {
+                    let kind = if kind == "function" && in_type { "method" } else { kind };
+                    let qualified_name = qualify_name(scope, &name, ".");
+                    let mut symbol = Symbol::from_node(kind, &name, &qualified_name, node, &self.source_code);
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbol.children = self.process_node_func_class(cursor, &qualified_name, is_type_kind(kind));
                         cursor.goto_parent();
                     }
+                    symbols.push(symbol);
                 },
-                _ => {
+                None => {
                     if cursor.goto_first_child() {
-                        result.push_str(&self.process_node_func_class(cursor));
+                        symbols.extend(self.process_node_func_class(cursor, scope, in_type));
                         cursor.goto_parent();
                     }
-                }
+                },
             }
 
             if !cursor.goto_next_sibling() {
@@ -273,12 +422,27 @@ impl TypeScriptSegmenter {
             }
         }
 
-        result
+        symbols
     }
 
-    // fn get_name(&self, node: Node) -> String {
-    //     node.child_by_field_name("name")
-    //         .map(|name_node| self.get_node_text(name_node))
-    //         .unwrap_or_else(|| "unnamed".to_string())
-    // }
+    fn get_symbol(&self, node: Node) -> Option<(&'static str, String)> {
+        let kind = match node.kind() {
+            "function_declaration" | "generator_function_declaration" if self.is_component(node, node) => "component",
+            "function_declaration" | "generator_function_declaration" | "function_signature" => "function",
+            "class_declaration" | "abstract_class_declaration" | "class" => "class",
+            "interface_declaration" => "interface",
+            "enum_declaration" => "enum",
+            "type_alias_declaration" => "type",
+            "internal_module" | "module" => "namespace",
+            "method_definition" | "method_signature" | "abstract_method_signature" => "method",
+            "variable_declarator" => match self.get_declared_function(node) {
+                Some(function) if self.is_component(node, function) => "component",
+                Some(_) => "function",
+                None if node.child_by_field_name("value").is_some_and(|value| value.kind() == "class") => "class",
+                None => return None,
+            },
+            _ => return None,
+        };
+        Some((kind, self.get_node_text(node.child_by_field_name("name")?)))
+    }
 }
\ No newline at end of file
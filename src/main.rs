@@ -1,17 +1,28 @@
+#![allow(clippy::new_ret_no_self)]
+
 mod code_segmenter;
 mod language_segmenters;
 mod file_processor;
+mod output_format;
+mod cli;
 
 use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
 use std::fs;
-use tokio;
 
-use crate::code_segmenter::CodeSegmenter;
+use crate::cli::{Cli, Command};
+use crate::code_segmenter::SegmenterFn;
 use crate::language_segmenters::*;
-use crate::file_processor::main_parser;
+use crate::file_processor::{FileFilter, ScanOptions, SummaryOptions, Verbosity, collect_directory_tree, main_parser, process_files};
+use crate::output_format::{get_language_stats, render_stats, render_symbols, render_tree_output};
+use clap::{CommandFactory, Parser};
+use clap::error::ErrorKind;
 use git2::Repository;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rand::{thread_rng, Rng};
 
 fn normalize_path(path: &Path) -> PathBuf {
     match path.canonicalize() {
@@ -20,88 +31,297 @@ fn normalize_path(path: &Path) -> PathBuf {
     }
 }
 
-#[tokio::main]
-async fn main() {
-    // Parse command-line arguments
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: {} <git-repo-url|directory-path>", args[0]);
-        std::process::exit(1);
+/// `_arch_<repo name>-<short commit>` in the working directory, the commit left
+/// out outside a git repository. `random_suffix` restores `_arch_<6 random chars>`.
+fn get_default_output_dir(directory_path: &Path, random_suffix: bool) -> PathBuf {
+    if random_suffix {
+        return PathBuf::from(format!("_arch_{}", get_random_suffix()));
     }
-    let input_path = &args[1];
-
-    // Remove the '--' prefix if present
-    let input_path = input_path.trim_start_matches("--");
-    
-    // Define the temp directory path based on the directory containing Cargo.toml
-    let cargo_manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set");
-    let mut temp_dir = PathBuf::from(cargo_manifest_dir);
-    temp_dir.push("temp/temp");
-
-    // Normalize the input path and handle cloning or using directly
-    let directory_path = if input_path.starts_with("http://") || input_path.starts_with("https://") {
-        // Clone the git repository into the temp directory
-        if temp_dir.exists() {
-            fs::remove_dir_all(&temp_dir).unwrap();
-        }
-        fs::create_dir_all(&temp_dir).unwrap();
-        match Repository::clone(input_path, &temp_dir) {
-            Ok(_) => normalize_path(&temp_dir),
-            Err(e) => {
-                eprintln!("Failed to clone the repository: {}", e);
-                std::process::exit(1);
+
+    let repo_name = directory_path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let commit = Repository::discover(directory_path).ok()
+        .and_then(|repo| Some(repo.head().ok()?.peel_to_commit().ok()?.id().to_string()[..7].to_string()));
+    match commit {
+        Some(commit) => PathBuf::from(format!("_arch_{}-{}", repo_name, commit)),
+        None => PathBuf::from(format!("_arch_{}", repo_name)),
+    }
+}
+
+fn is_url(input_path: &str) -> bool {
+    input_path.starts_with("http://") || input_path.starts_with("https://")
+}
+
+fn get_random_suffix() -> String {
+    thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(6)
+        .map(char::from)
+        .collect()
+}
+
+/// The directory to process, removing its clone once it goes out of scope.
+struct InputDirectory {
+    path: PathBuf,
+    clone_dir: Option<PathBuf>,
+}
+
+impl Drop for InputDirectory {
+    fn drop(&mut self) {
+        if let Some(clone_dir) = &self.clone_dir {
+            if let Err(e) = fs::remove_dir_all(clone_dir) {
+                eprintln!("Failed to remove {}: {}", clone_dir.display(), e);
             }
         }
-    } else {
+    }
+}
+
+/// Clones URLs into a fresh temp directory, other inputs have to be a directory.
+fn get_input_directory(input_path: &str) -> Result<InputDirectory, String> {
+    if !is_url(input_path) {
         // Normalize and use the directory path directly
-        normalize_path(Path::new(input_path))
-        
-    };
+        let path = normalize_path(Path::new(input_path));
+        if !path.is_dir() {
+            return Err(format!("{} is not a directory", input_path));
+        }
+        return Ok(InputDirectory { path, clone_dir: None });
+    }
+
+    // Named after the repository, as that's the root of the tree and the output name
+    let repo_name = input_path.trim_end_matches('/').rsplit('/').next().unwrap_or("").trim_end_matches(".git");
+    if repo_name.is_empty() || repo_name.starts_with('.') || repo_name.contains(['\\', ':']) {
+        return Err(format!("Can't tell the repository name from {}", input_path));
+    }
+
+    // `create_dir` fails on an existing directory, so concurrent runs never share one
+    let clone_dir = env::temp_dir().join(format!("repo_parser_{}", get_random_suffix()));
+    fs::create_dir(&clone_dir).map_err(|e| format!("Failed to create {}: {}", clone_dir.display(), e))?;
+    let mut input = InputDirectory { path: clone_dir.join(repo_name), clone_dir: Some(clone_dir) };
+    match Repository::clone(input_path, &input.path) {
+        Ok(_) => {
+            input.path = normalize_path(&input.path);
+            Ok(input)
+        },
+        Err(e) => Err(format!("Failed to clone the repository: {}", e)),
+    }
+}
+
+fn get_glob_set(globs: &[Glob]) -> Option<GlobSet> {
+    if globs.is_empty() {
+        return None;
+    }
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(glob.clone());
+    }
+    match builder.build() {
+        Ok(glob_set) => Some(glob_set),
+        Err(e) => Cli::command().error(ErrorKind::InvalidValue, e).exit(),
+    }
+}
+
+async fn run(
+    command: Command,
+    language_extensions: HashMap<String, String>,
+    language_segmenters: HashMap<String, SegmenterFn>,
+    scan_options: &ScanOptions,
+) -> Result<(), String> {
+    match command {
+        Command::Summarize(args) => {
+            let input = get_input_directory(&args.input)?;
+            let directory_path = &input.path;
+            let summary_options = SummaryOptions {
+                output_dir: args.output.unwrap_or_else(|| get_default_output_dir(directory_path, args.random_suffix)),
+                overwrite: args.overwrite,
+                write_index: args.index,
+                format: args.format,
+            };
+            let directory_path_str = directory_path.to_str().unwrap().to_string();
+            let arch_dir = main_parser(directory_path_str, language_extensions, language_segmenters, scan_options, summary_options).await?;
+            // Scripts pick the output up from here
+            println!("{}", arch_dir.display());
+        },
+        Command::Tree(args) => {
+            let input = get_input_directory(&args.input)?;
+            let directory_path = &input.path;
+            let tree = collect_directory_tree(directory_path, None, &scan_options.filter).await;
+            print!("{}", render_tree_output(&tree, args.format).map_err(|e| e.to_string())?);
+        },
+        Command::Symbols(args) => {
+            let input = get_input_directory(&args.input)?;
+            let directory_path = &input.path;
+            let tree = collect_directory_tree(directory_path, None, &scan_options.filter).await;
+            let files = process_files(directory_path, &tree, Arc::new(language_extensions), Arc::new(language_segmenters), None, scan_options).await;
+            print!("{}", render_symbols(&files.iter().collect::<Vec<_>>(), args.format).map_err(|e| e.to_string())?);
+        },
+        Command::Stats(args) => {
+            let input = get_input_directory(&args.input)?;
+            let directory_path = &input.path;
+            let tree = collect_directory_tree(directory_path, None, &scan_options.filter).await;
+            let files = process_files(directory_path, &tree, Arc::new(language_extensions), Arc::new(language_segmenters), None, scan_options).await;
+            let stats = get_language_stats(&files.iter().collect::<Vec<_>>());
+            print!("{}", render_stats(&stats, args.format).map_err(|e| e.to_string())?);
+        },
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
 
     // Define language extensions and segmenters
     let language_extensions: HashMap<String, String> = [
         ("py", "python"),
+        ("pyi", "python"),
         ("js", "js"),
-        ("cobol", "cobol"),
+        ("mjs", "js"),
+        ("cjs", "js"),
+        ("cbl", "cobol"),
+        ("cob", "cobol"),
+        ("cpy", "cobol"),
         ("c", "c"),
+        ("h", "header"),
         ("cpp", "cpp"),
+        ("cc", "cpp"),
+        ("cxx", "cpp"),
+        ("hpp", "cpp"),
+        ("hh", "cpp"),
         ("cs", "csharp"),
         ("rb", "ruby"),
         ("scala", "scala"),
+        ("sc", "scala"),
         ("rs", "rust"),
         ("go", "go"),
         ("kt", "kotlin"),
+        ("kts", "kotlin"),
         ("lua", "lua"),
+        ("r", "r"),
+        ("R", "r"),
+        ("sh", "bash"),
+        ("bash", "bash"),
         ("pl", "perl"),
+        ("pm", "perl"),
         ("ts", "ts"),
+        ("mts", "ts"),
+        ("cts", "ts"),
+        ("d.ts", "dts"),
+        ("tsx", "tsx"),
+        ("jsx", "tsx"),
         ("java", "java"),
         ("php", "php"),
+        ("swift", "swift"),
+        ("m", "objc"),
+        ("mm", "objc"),
         ("ex", "elixir"),
         ("exs", "elixir"),
+        ("erl", "erlang"),
+        ("hrl", "erlang"),
+        ("md", "markdown"),
+        ("markdown", "markdown"),
+        ("html", "html"),
+        ("htm", "html"),
+        ("json", "json"),
     ].iter().map(|&(k, v)| (k.to_string(), v.to_string())).collect();
 
-    let language_segmenters: HashMap<String, fn(String) -> Box<dyn CodeSegmenter>> = [
-        ("python".to_string(), PythonSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        ("js".to_string(), JavaScriptSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // Uncomment and add other segmenters as needed
-        // ("cobol".to_string(), CobolSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("c".to_string(), CSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("cpp".to_string(), CppSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("csharp".to_string(), CSharpSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("ruby".to_string(), RubySegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("scala".to_string(), ScalaSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        ("rust".to_string(), RustSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("go".to_string(), GoSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("kotlin".to_string(), KotlinSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("lua".to_string(), LuaSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("perl".to_string(), PerlSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        ("ts".to_string(), TypeScriptSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        ("java".to_string(), JavaSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("php".to_string(), PhpSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
-        // ("elixir".to_string(), ElixirSegmenter::new as fn(String) -> Box<dyn CodeSegmenter>),
+    let language_segmenters: HashMap<String, SegmenterFn> = [
+        ("python".to_string(), PythonSegmenter::new as SegmenterFn),
+        ("js".to_string(), JavaScriptSegmenter::new as SegmenterFn),
+        ("cobol".to_string(), CobolSegmenter::new as SegmenterFn),
+        ("c".to_string(), CSegmenter::new as SegmenterFn),
+        ("cpp".to_string(), CppSegmenter::new as SegmenterFn),
+        ("csharp".to_string(), CSharpSegmenter::new as SegmenterFn),
+        ("ruby".to_string(), RubySegmenter::new as SegmenterFn),
+        ("scala".to_string(), ScalaSegmenter::new as SegmenterFn),
+        ("rust".to_string(), RustSegmenter::new as SegmenterFn),
+        ("go".to_string(), GoSegmenter::new as SegmenterFn),
+        ("kotlin".to_string(), KotlinSegmenter::new as SegmenterFn),
+        ("lua".to_string(), LuaSegmenter::new as SegmenterFn),
+        ("perl".to_string(), PerlSegmenter::new as SegmenterFn),
+        ("ts".to_string(), TypeScriptSegmenter::new as SegmenterFn),
+        ("tsx".to_string(), TypeScriptSegmenter::new_tsx as SegmenterFn),
+        ("dts".to_string(), TypeScriptSegmenter::new_declaration as SegmenterFn),
+        ("java".to_string(), JavaSegmenter::new as SegmenterFn),
+        ("swift".to_string(), SwiftSegmenter::new as SegmenterFn),
+        ("objc".to_string(), ObjcSegmenter::new as SegmenterFn),
+        ("header".to_string(), HeaderSegmenter::new as SegmenterFn),
+        ("r".to_string(), RSegmenter::new as SegmenterFn),
+        ("bash".to_string(), BashSegmenter::new as SegmenterFn),
+        ("php".to_string(), PhpSegmenter::new as SegmenterFn),
+        ("elixir".to_string(), ElixirSegmenter::new as SegmenterFn),
+        ("erlang".to_string(), ErlangSegmenter::new as SegmenterFn),
+        ("markdown".to_string(), MarkdownSegmenter::new as SegmenterFn),
+        ("html".to_string(), HtmlSegmenter::new as SegmenterFn),
+        ("json".to_string(), JsonSegmenter::new as SegmenterFn),
     ].iter().cloned().collect();
 
-    // Convert directory path to string and pass to main_parser
-    let directory_path_str = directory_path.to_str().unwrap().to_string();
-    main_parser(directory_path_str, language_extensions, language_segmenters).await;
+    // Languages as `--language` names them, with every extension they cover. Segmenters
+    // are keyed by grammar instead, `.jsx` and `.tsx` share one and headers have their own
+    let language_names: HashMap<&str, Vec<&str>> = [
+        ("bash", vec!["sh", "bash"]),
+        ("c", vec!["c", "h"]),
+        ("cobol", vec!["cbl", "cob", "cpy"]),
+        ("cpp", vec!["cpp", "cc", "cxx", "hpp", "hh", "h"]),
+        ("csharp", vec!["cs"]),
+        ("elixir", vec!["ex", "exs"]),
+        ("erlang", vec!["erl", "hrl"]),
+        ("go", vec!["go"]),
+        ("html", vec!["html", "htm"]),
+        ("java", vec!["java"]),
+        ("javascript", vec!["js", "mjs", "cjs", "jsx"]),
+        ("js", vec!["js", "mjs", "cjs", "jsx"]),
+        ("json", vec!["json"]),
+        ("kotlin", vec!["kt", "kts"]),
+        ("lua", vec!["lua"]),
+        ("markdown", vec!["md", "markdown"]),
+        ("objc", vec!["m", "mm", "h"]),
+        ("perl", vec!["pl", "pm"]),
+        ("php", vec!["php"]),
+        ("python", vec!["py", "pyi"]),
+        ("r", vec!["r", "R"]),
+        ("ruby", vec!["rb"]),
+        ("rust", vec!["rs"]),
+        ("scala", vec!["scala", "sc"]),
+        ("swift", vec!["swift"]),
+        ("ts", vec!["ts", "mts", "cts", "d.ts", "tsx"]),
+        ("typescript", vec!["ts", "mts", "cts", "d.ts", "tsx"]),
+    ].into_iter().collect();
+
+    // Unknown languages are reported like any other invalid value
+    let mut extensions = Vec::new();
+    for language in &cli.languages {
+        match language_names.get(language.to_lowercase().as_str()) {
+            Some(language_extensions) => extensions.extend(language_extensions.iter().map(|extension| extension.to_string())),
+            None => {
+                let mut known_languages: Vec<&str> = language_names.keys().copied().collect();
+                known_languages.sort();
+                Cli::command()
+                    .error(ErrorKind::InvalidValue, format!("unknown language '{}', expected one of: {}", language, known_languages.join(", ")))
+                    .exit();
+            }
+        }
+    }
+
+    let scan_options = ScanOptions {
+        filter: FileFilter {
+            extensions,
+            include: get_glob_set(&cli.include),
+            exclude: get_glob_set(&cli.exclude),
+        },
+        concurrency: cli.concurrency
+            .map(|concurrency| concurrency as usize)
+            .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |count| count.get())),
+        verbosity: match (cli.quiet, cli.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, _) => Verbosity::Verbose,
+        },
+    };
+
+    match run(cli.command, language_extensions, language_segmenters, &scan_options).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use clap::ValueEnum;
+use serde::Serialize;
+use crate::code_segmenter::{Symbol, render_index};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Plain text, files separated by dotted lines in `summary.txt`
+    #[value(alias = "txt")]
+    Text,
+    /// A single JSON array of records
+    Json,
+    /// JSON Lines, one record per line
+    Jsonl,
+    /// A report with fenced code blocks
+    #[value(alias = "md")]
+    Markdown,
+}
+
+impl OutputFormat {
+    pub fn summary_file_name(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "summary.txt",
+            OutputFormat::Json => "summary.json",
+            OutputFormat::Jsonl => "summary.jsonl",
+            OutputFormat::Markdown => "summary.md",
+        }
+    }
+}
+
+/// The header comes first, followed by one record per processed file.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Record {
+    Header {
+        root: String,
+        tree: TreeNode,
+    },
+    File(FileRecord),
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreeNode {
+    pub name: String,
+    /// `file` or `directory`.
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<TreeNode>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileRecord {
+    /// Relative to the input directory, `/` separated.
+    pub path: String,
+    pub language: String,
+    /// `None` when the file couldn't be read.
+    pub segmenter: Option<&'static str>,
+    pub text: String,
+    pub symbols: Vec<Symbol>,
+    pub source_bytes: usize,
+    pub source_lines: usize,
+    pub text_bytes: usize,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LanguageStats {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+}
+
+#[derive(Serialize)]
+struct SymbolRecord<'a> {
+    path: &'a str,
+    language: &'a str,
+    symbols: &'a [Symbol],
+}
+
+pub fn get_files(records: &[Record]) -> Vec<&FileRecord> {
+    records.iter()
+        .filter_map(|record| match record {
+            Record::File(file) => Some(file),
+            Record::Header { .. } => None,
+        })
+        .collect()
+}
+
+/// File and source line counts per language, sorted by language.
+pub fn get_language_stats(files: &[&FileRecord]) -> Vec<LanguageStats> {
+    let mut languages: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for file in files {
+        let counts = languages.entry(file.language.as_str()).or_default();
+        counts.0 += 1;
+        counts.1 += file.source_lines;
+    }
+    languages.into_iter()
+        .map(|(language, (files, lines))| LanguageStats { language: language.to_string(), files, lines })
+        .collect()
+}
+
+/// The symbols of every file, e.g. `method User.save L10-42` under the file's path.
+pub fn render_symbol_index(files: &[&FileRecord]) -> String {
+    let mut result = String::new();
+    for file in files {
+        if !file.symbols.is_empty() {
+            write!(&mut result, "\n{}\n{}\n", file.path, render_index(&file.symbols)).unwrap();
+        }
+    }
+    result
+}
+
+pub fn render_tree_output(tree: &TreeNode, format: OutputFormat) -> serde_json::Result<String> {
+    let mut result = String::new();
+    match format {
+        OutputFormat::Text => render_tree(tree, "", true, &mut result),
+        OutputFormat::Json => result = serde_json::to_string_pretty(tree)? + "\n",
+        OutputFormat::Jsonl => result = serde_json::to_string(tree)? + "\n",
+        OutputFormat::Markdown => {
+            result.push_str("```text\n");
+            render_tree(tree, "", true, &mut result);
+            result.push_str("```\n");
+        },
+    }
+    Ok(result)
+}
+
+pub fn render_symbols(files: &[&FileRecord], format: OutputFormat) -> serde_json::Result<String> {
+    let records: Vec<SymbolRecord> = files.iter()
+        .filter(|file| !file.symbols.is_empty())
+        .map(|file| SymbolRecord { path: &file.path, language: &file.language, symbols: &file.symbols })
+        .collect();
+    match format {
+        OutputFormat::Text => Ok(render_symbol_index(files).trim_start().to_string()),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(&records)? + "\n"),
+        OutputFormat::Jsonl => render_json_lines(&records),
+        OutputFormat::Markdown => {
+            let mut result = String::new();
+            for record in &records {
+                writeln!(&mut result, "### {}\n", record.path).unwrap();
+                for line in render_index(record.symbols).lines() {
+                    writeln!(&mut result, "- `{}`", line).unwrap();
+                }
+                result.push('\n');
+            }
+            Ok(result)
+        },
+    }
+}
+
+pub fn render_stats(stats: &[LanguageStats], format: OutputFormat) -> serde_json::Result<String> {
+    match format {
+        OutputFormat::Text => {
+            let mut result = format!("{:<12} {:>8} {:>10}\n", "language", "files", "lines");
+            for language in stats {
+                writeln!(&mut result, "{:<12} {:>8} {:>10}", language.language, language.files, language.lines).unwrap();
+            }
+            let (files, lines) = get_totals(stats);
+            writeln!(&mut result, "{:<12} {:>8} {:>10}", "total", files, lines).unwrap();
+            Ok(result)
+        },
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(stats)? + "\n"),
+        OutputFormat::Jsonl => render_json_lines(stats),
+        OutputFormat::Markdown => Ok(render_language_table(stats)),
+    }
+}
+
+fn render_json_lines<T: Serialize>(records: &[T]) -> serde_json::Result<String> {
+    let mut result = String::new();
+    for record in records {
+        result.push_str(&serde_json::to_string(record)?);
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+fn get_totals(stats: &[LanguageStats]) -> (usize, usize) {
+    stats.iter().fold((0, 0), |(files, lines), language| (files + language.files, lines + language.lines))
+}
+
+fn render_language_table(stats: &[LanguageStats]) -> String {
+    let mut result = String::new();
+    writeln!(&mut result, "| Language | Files | Lines |").unwrap();
+    writeln!(&mut result, "| --- | ---: | ---: |").unwrap();
+    for language in stats {
+        writeln!(&mut result, "| {} | {} | {} |", language.language, language.files, language.lines).unwrap();
+    }
+    let (files, lines) = get_totals(stats);
+    writeln!(&mut result, "| **Total** | **{}** | **{}** |", files, lines).unwrap();
+    result
+}
+
+/// Renders the records in the given format, in the order they were collected.
+pub fn render_records(records: &[Record], format: OutputFormat) -> serde_json::Result<String> {
+    match format {
+        OutputFormat::Text => Ok(render_text(records)),
+        OutputFormat::Markdown => Ok(render_markdown(records)),
+        OutputFormat::Jsonl => render_json_lines(records),
+        OutputFormat::Json => serde_json::to_string_pretty(records),
+    }
+}
+
+fn render_text(records: &[Record]) -> String {
+    let separator = "\n.................................................................\n";
+    let mut result = String::new();
+    for record in records {
+        match record {
+            Record::Header { tree, .. } => {
+                render_tree(tree, "", true, &mut result);
+                result.push_str(separator);
+            },
+            // Unreadable files only show up in error.txt
+            Record::File(file) if file.segmenter.is_some() => {
+                write!(&mut result, "\n{}\n\n{}{}", file.path, file.text, separator).unwrap();
+            },
+            Record::File(_) => {},
+        }
+    }
+    result
+}
+
+fn render_markdown(records: &[Record]) -> String {
+    let mut result = String::new();
+    let files = get_files(records);
+
+    for record in records {
+        if let Record::Header { root, .. } = record {
+            writeln!(&mut result, "# {}\n", root).unwrap();
+        }
+    }
+
+    result.push_str(&render_language_table(&get_language_stats(&files)));
+    result.push('\n');
+
+    // GitHub numbers repeated anchors in document order
+    let mut anchors = HashMap::new();
+    let mut next_anchor = |heading: &str| {
+        let anchor = get_heading_anchor(heading);
+        let count = anchors.entry(anchor.clone()).or_insert(0);
+        *count += 1;
+        if *count == 1 { anchor } else { format!("{}-{}", anchor, *count - 1) }
+    };
+    for record in records {
+        if let Record::Header { root, .. } = record {
+            next_anchor(root);
+        }
+    }
+    writeln!(&mut result, "## Contents\n").unwrap();
+    next_anchor("Contents");
+    writeln!(&mut result, "- [Directory tree](#{})", next_anchor("Directory tree")).unwrap();
+    writeln!(&mut result, "- [Files](#{})", next_anchor("Files")).unwrap();
+    for file in &files {
+        writeln!(&mut result, "  - [{}](#{})", file.path, next_anchor(&file.path)).unwrap();
+    }
+
+    for record in records {
+        if let Record::Header { tree, .. } = record {
+            let mut tree_text = String::new();
+            render_tree(tree, "", true, &mut tree_text);
+            writeln!(&mut result, "\n## Directory tree\n\n```text\n{}```", tree_text).unwrap();
+        }
+    }
+
+    writeln!(&mut result, "\n## Files").unwrap();
+    for file in &files {
+        writeln!(&mut result, "\n### {}\n", file.path).unwrap();
+        for error in &file.errors {
+            writeln!(&mut result, "> {}\n", error).unwrap();
+        }
+        if file.segmenter.is_some() {
+            // The fence has to be longer than any backtick run in the code
+            let longest_run = file.text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat(longest_run.max(2) + 1);
+            writeln!(&mut result, "{}{}\n{}\n{}", fence, get_fence_language(&file.language), file.text, fence).unwrap();
+        }
+    }
+
+    result
+}
+
+fn render_tree(node: &TreeNode, prefix: &str, is_last: bool, result: &mut String) {
+    let entry_prefix = if is_last { "└── " } else { "├── " };
+    writeln!(result, "{}{}{}", prefix, entry_prefix, node.name).unwrap();
+    if let Some(children) = &node.children {
+        let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+        for (i, child) in children.iter().enumerate() {
+            render_tree(child, &child_prefix, i == children.len() - 1, result);
+        }
+    }
+}
+
+/// GitHub's anchor for a heading: lower case, spaces to dashes, other punctuation dropped.
+fn get_heading_anchor(heading: &str) -> String {
+    heading.to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Info string for the fenced block, keyed like `language_segmenters` in `main.rs`.
+fn get_fence_language(language: &str) -> &str {
+    match language {
+        "js" => "javascript",
+        "ts" | "dts" => "typescript",
+        "header" => "c",
+        "objc" => "objectivec",
+        language => language,
+    }
+}